@@ -0,0 +1,675 @@
+use std::{path::PathBuf, time::Instant};
+
+use chrono::{Datelike, NaiveDateTime, NaiveTime, Timelike, Weekday};
+use crossterm::event::{KeyCode, KeyEvent};
+use ratatui::{
+    Frame,
+    layout::{Constraint, Direction, Layout, Rect},
+    style::{Color, Modifier, Stylize},
+    text::{Line, Text},
+};
+use serde::{Deserialize, Serialize};
+
+use super::alert::Alert;
+use super::config::Config;
+use super::input::{InputEvent, InputLine};
+
+#[derive(Debug, Default)]
+pub struct AlarmState {
+    alarms: Vec<Alarm>,
+    selected: usize,
+    /// A new alarm being typed.
+    input: Option<InputLine>,
+    /// Feedback for the last input, e.g. a parse error.
+    message: Option<String>,
+    /// Where alarms are saved; `None` keeps them in memory only.
+    path: Option<PathBuf>,
+    /// Why the alarms file could not be read in full, and so is not saved over.
+    load_error: Option<String>,
+    /// Wall-clock time of the previous tick. Alarms due after it and up to now ring.
+    last_check: Option<NaiveDateTime>,
+}
+#[derive(Debug, Clone, PartialEq)]
+pub struct Alarm {
+    time: NaiveTime,
+    label: String,
+    enabled: bool,
+    /// Bit `i` is set if the alarm repeats on the `i`-th day from Monday; 0 rings once.
+    days: u8,
+    /// When a snoozed alarm rings again.
+    snoozed_until: Option<NaiveDateTime>,
+    alert: Option<Alert>,
+}
+/// On-disk format of the alarms file.
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct AlarmFile {
+    #[serde(default)]
+    alarm: Vec<AlarmEntry>,
+}
+#[derive(Debug, Serialize, Deserialize)]
+struct AlarmEntry {
+    /// `HH:MM` or `HH:MM:SS`.
+    time: String,
+    #[serde(default)]
+    label: String,
+    #[serde(default = "enabled_default")]
+    enabled: bool,
+    /// Anything [`parse_days`] accepts.
+    #[serde(default = "days_default")]
+    days: String,
+}
+fn enabled_default() -> bool {
+    true
+}
+fn days_default() -> String {
+    "once".to_string()
+}
+
+const WEEKDAYS: u8 = 0b0011111;
+const WEEKENDS: u8 = 0b1100000;
+const DAILY: u8 = 0b1111111;
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum ParseAlarmError {
+    Empty,
+    /// A time of day that could not be read.
+    Time(String),
+    /// A day or a day range that could not be read.
+    Days(String),
+}
+impl std::fmt::Display for ParseAlarmError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ParseAlarmError::Empty => write!(f, "empty alarm"),
+            ParseAlarmError::Time(s) => write!(f, "invalid time: {s}"),
+            ParseAlarmError::Days(s) => write!(f, "invalid days: {s}"),
+        }
+    }
+}
+impl std::error::Error for ParseAlarmError {}
+
+fn parse_time(s: &str) -> Result<NaiveTime, ParseAlarmError> {
+    NaiveTime::parse_from_str(s, "%H:%M")
+        .or_else(|_| NaiveTime::parse_from_str(s, "%H:%M:%S"))
+        .map_err(|_| ParseAlarmError::Time(s.to_string()))
+}
+
+/// Parses recurrence such as `once`, `daily`, `weekdays`, `weekends`, `mon,wed` or `mon-fri`.
+pub fn parse_days(s: &str) -> Result<u8, ParseAlarmError> {
+    let invalid = || ParseAlarmError::Days(s.to_string());
+    let day = |d: &str| {
+        d.trim()
+            .parse::<Weekday>()
+            .map(|d| d.num_days_from_monday())
+            .map_err(|_| invalid())
+    };
+    match s.trim().to_lowercase().as_str() {
+        "once" => return Ok(0),
+        "daily" | "everyday" => return Ok(DAILY),
+        "weekdays" => return Ok(WEEKDAYS),
+        "weekends" => return Ok(WEEKENDS),
+        _ => {}
+    }
+    let mut days = 0;
+    for part in s.split(',') {
+        match part.split_once('-') {
+            Some((from, to)) => {
+                // 週をまたぐ範囲 (sat-mon など) も受け付ける
+                let (from, to) = (day(from)?, day(to)?);
+                let mut d = from;
+                loop {
+                    days |= 1 << d;
+                    if d == to {
+                        break;
+                    }
+                    d = (d + 1) % 7;
+                }
+            }
+            None => days |= 1 << day(part)?,
+        }
+    }
+    Ok(days)
+}
+
+/// Formats recurrence in the form [`parse_days`] reads.
+fn format_days(days: u8) -> String {
+    match days {
+        0 => "once".to_string(),
+        DAILY => "daily".to_string(),
+        WEEKDAYS => "weekdays".to_string(),
+        WEEKENDS => "weekends".to_string(),
+        _ => (0..7)
+            .filter(|i| days & (1 << i) != 0)
+            .map(|i| Weekday::try_from(i as u8).unwrap().to_string())
+            .collect::<Vec<_>>()
+            .join(","),
+    }
+}
+
+/// Parses an alarm such as `7:30 Wake up weekdays`: a time, a label and optional recurrence.
+pub fn parse_alarm(s: &str) -> Result<Alarm, ParseAlarmError> {
+    let mut tokens: Vec<&str> = s.split_whitespace().collect();
+    if tokens.is_empty() {
+        return Err(ParseAlarmError::Empty);
+    }
+    let time = parse_time(tokens.remove(0))?;
+    let days = match tokens.last().map(|t| parse_days(t)) {
+        Some(Ok(days)) => {
+            tokens.pop();
+            days
+        }
+        _ => 0,
+    };
+    Ok(Alarm {
+        time,
+        label: tokens.join(" "),
+        enabled: true,
+        days,
+        snoozed_until: None,
+        alert: None,
+    })
+}
+
+fn rings_on(days: u8, weekday: Weekday) -> bool {
+    days & (1 << weekday.num_days_from_monday()) != 0
+}
+
+/// The first time after `t` at which the alarm rings, ignoring snooze and the enabled flag.
+fn next_occurrence(alarm: &Alarm, t: NaiveDateTime) -> Option<NaiveDateTime> {
+    (0..=7)
+        .filter_map(|offset| t.date().checked_add_days(chrono::Days::new(offset)))
+        .filter(|date| alarm.days == 0 || rings_on(alarm.days, date.weekday()))
+        .map(|date| date.and_time(alarm.time))
+        .find(|&candidate| candidate > t)
+}
+
+/// When the alarm rings next, including snooze.
+fn next_ring(alarm: &Alarm, t: NaiveDateTime) -> Option<NaiveDateTime> {
+    let scheduled = alarm.enabled.then(|| next_occurrence(alarm, t)).flatten();
+    match (scheduled, alarm.snoozed_until) {
+        (Some(a), Some(b)) => Some(std::cmp::min(a, b)),
+        (a, b) => a.or(b),
+    }
+}
+
+/// Loads the alarms saved in `path`, and saves them there from now on.
+///
+/// If the file cannot be read in full, the alarms that could be read are kept in memory only so
+/// that saving does not overwrite the rest of the file.
+pub fn load_alarms(state: &mut AlarmState, path: PathBuf) {
+    match super::config::load::<AlarmFile>(&path) {
+        Ok(file) => {
+            for entry in file.alarm {
+                let alarm = parse_time(&entry.time).and_then(|time| {
+                    Ok(Alarm {
+                        time,
+                        label: entry.label.clone(),
+                        enabled: entry.enabled,
+                        days: parse_days(&entry.days)?,
+                        snoozed_until: None,
+                        alert: None,
+                    })
+                });
+                match alarm {
+                    Ok(alarm) => state.alarms.push(alarm),
+                    Err(e) => state.load_error = Some(format!("{}: {e}", entry.time)),
+                }
+            }
+        }
+        Err(e) => state.load_error = Some(format!("Failed to load alarms: {e}")),
+    }
+    match &state.load_error {
+        Some(e) => state.message = Some(format!("{e} (changes will not be saved)")),
+        None => state.path = Some(path),
+    }
+}
+
+fn save_alarms(state: &mut AlarmState) {
+    let Some(path) = &state.path else {
+        if let Some(e) = &state.load_error {
+            state.message = Some(format!("Not saved: {e}"));
+        }
+        return;
+    };
+    let file = AlarmFile {
+        alarm: state
+            .alarms
+            .iter()
+            .map(|a| AlarmEntry {
+                time: a.time.format("%H:%M:%S").to_string(),
+                label: a.label.clone(),
+                enabled: a.enabled,
+                days: format_days(a.days),
+            })
+            .collect(),
+    };
+    if let Err(e) = super::config::save(path, &file) {
+        state.message = Some(format!("Failed to save alarms: {e}"));
+    }
+}
+
+/// Starts the alerts of the alarms that became due since the previous tick.
+///
+/// Returns whether the bell should ring now.
+pub fn tick_alarms(
+    state: &mut AlarmState,
+    now: Instant,
+    local_now: chrono::DateTime<chrono::Local>,
+    config: &Config,
+) -> bool {
+    let wall = local_now.naive_local();
+    // 時計が戻った場合は何も鳴らさない
+    let last = state.last_check.replace(wall).filter(|&last| last < wall);
+    let mut fired_once = false;
+    if let Some(last) = last {
+        for alarm in &mut state.alarms {
+            if next_ring(alarm, last).is_some_and(|t| t <= wall) {
+                alarm.alert = Some(Alert::new(now));
+                alarm.snoozed_until = None;
+                // 一度きりのアラームは鳴ったら無効にする
+                if alarm.days == 0 && alarm.enabled {
+                    alarm.enabled = false;
+                    fired_once = true;
+                }
+            }
+        }
+    }
+    if fired_once {
+        save_alarms(state);
+    }
+    state
+        .alarms
+        .iter_mut()
+        .filter_map(|a| a.alert.as_mut())
+        .fold(false, |ring, alert| alert.tick(now, &config.alert) | ring)
+}
+
+/// Whether a ringing alarm wants the screen inverted now.
+pub fn is_flashing(state: &AlarmState, now: Instant, config: &Config) -> bool {
+    state.alarms.iter().any(|alarm| {
+        alarm
+            .alert
+            .as_ref()
+            .is_some_and(|a| a.is_flashing(now, &config.alert))
+    })
+}
+
+fn ringing<'a>(
+    state: &'a AlarmState,
+    now: Instant,
+    config: &'a Config,
+) -> impl Iterator<Item = &'a Alarm> {
+    state.alarms.iter().filter(move |alarm| {
+        alarm
+            .alert
+            .as_ref()
+            .is_some_and(|a| a.is_active(now, &config.alert))
+    })
+}
+
+/// The next alarm to ring, for the Clock tab.
+pub fn next_alarm(
+    state: &AlarmState,
+    local_now: chrono::DateTime<chrono::Local>,
+) -> Option<(NaiveDateTime, &str)> {
+    let wall = local_now.naive_local();
+    state
+        .alarms
+        .iter()
+        .filter_map(|alarm| next_ring(alarm, wall).map(|t| (t, &alarm.label[..])))
+        .min_by_key(|&(t, _)| t)
+}
+
+/// Formats the next alarm as a line for the Clock tab.
+pub fn next_alarm_text(
+    state: &AlarmState,
+    local_now: chrono::DateTime<chrono::Local>,
+) -> Option<String> {
+    next_alarm(state, local_now).map(|(t, label)| {
+        format!("Next alarm: {} {}", t.format("%a %H:%M"), label)
+            .trim_end()
+            .to_string()
+    })
+}
+
+/// Shows the wall clock, with the time left until the next alarm in the readout.
+pub fn update_alarm(
+    state: &AlarmState,
+    now: Instant,
+    local_now: chrono::DateTime<chrono::Local>,
+    config: &Config,
+) -> super::DisplayData {
+    let mut display = super::clock_tab::update_chrono(local_now, &config.clock);
+    if let Some(alarm) = ringing(state, now, config).next() {
+        display.block_title = "Alarm!".to_string();
+        display.block_content = match &alarm.label[..] {
+            "" => alarm.time.format("%H:%M").to_string(),
+            label => label.to_string(),
+        };
+        display.content_color = Color::Red;
+    } else if let Some((t, _)) = next_alarm(state, local_now) {
+        let left = t - local_now.naive_local();
+        display.block_title = format!("Next {}", t.format("%a %H:%M"));
+        display.block_content = format!(
+            "-{}:{:02}:{:02}",
+            left.num_hours(),
+            left.num_minutes() % 60,
+            left.num_seconds() % 60
+        );
+    } else {
+        display.block_title = "No alarms".to_string();
+    }
+    display
+}
+
+/// Whether a new alarm is being typed, so that the tab should receive every key.
+pub fn is_typing(state: &AlarmState) -> bool {
+    state.input.is_some()
+}
+
+pub fn handle_key_event(
+    state: &mut AlarmState,
+    key: &KeyEvent,
+    local_now: chrono::DateTime<chrono::Local>,
+    config: &Config,
+) {
+    if let Some(input) = &mut state.input {
+        match input.handle_key_event(key) {
+            InputEvent::Pending => {}
+            InputEvent::Submit(text) => {
+                state.input = None;
+                match parse_alarm(&text) {
+                    Ok(alarm) => {
+                        state.alarms.push(alarm);
+                        state.selected = state.alarms.len() - 1;
+                        save_alarms(state);
+                    }
+                    Err(e) => state.message = Some(e.to_string()),
+                }
+            }
+            InputEvent::Cancel => state.input = None,
+        }
+        return;
+    }
+    state.message = None;
+    match (key.modifiers, key.code) {
+        (_, KeyCode::Up) => state.selected = state.selected.saturating_sub(1),
+        (_, KeyCode::Down) => {
+            state.selected =
+                std::cmp::min(state.selected + 1, state.alarms.len().saturating_sub(1));
+        }
+        (_, KeyCode::Char('a')) => {
+            state.input = Some(InputLine::new("Alarm:"));
+        }
+        (_, KeyCode::Char('x')) if state.selected < state.alarms.len() => {
+            state.alarms.remove(state.selected);
+            state.selected = std::cmp::min(state.selected, state.alarms.len().saturating_sub(1));
+            save_alarms(state);
+        }
+        (_, KeyCode::Char(' ')) => {
+            if let Some(alarm) = state.alarms.get_mut(state.selected) {
+                alarm.enabled = !alarm.enabled;
+                alarm.snoozed_until = None;
+                save_alarms(state);
+            }
+        }
+        (_, KeyCode::Char('d')) => {
+            for alarm in &mut state.alarms {
+                alarm.alert = None;
+            }
+        }
+        (_, KeyCode::Char('z')) => {
+            let until = local_now.naive_local()
+                + chrono::TimeDelta::minutes(config.alert.snooze_minutes as i64);
+            for alarm in &mut state.alarms {
+                if alarm.alert.take().is_some() {
+                    alarm.snoozed_until = Some(until);
+                }
+            }
+        }
+        _ => {}
+    }
+}
+
+pub fn render_panel(
+    frame: &mut Frame,
+    panel_area: Rect,
+    state: &AlarmState,
+    now: Instant,
+    local_now: chrono::DateTime<chrono::Local>,
+    config: &Config,
+) {
+    let panel_layout = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints(vec![
+            Constraint::Min(0),
+            Constraint::Length(1),
+            Constraint::Length(1),
+            Constraint::Length(1),
+            Constraint::Length(std::cmp::max(state.alarms.len(), 1) as u16),
+            Constraint::Length(1),
+            Constraint::Length(1),
+            Constraint::Min(0),
+        ])
+        .split(panel_area);
+
+    frame.render_widget(
+        Text::from("[a]: Add  [x]: Delete  [Space]: On / Off")
+            .add_modifier(Modifier::DIM)
+            .centered(),
+        panel_layout[1],
+    );
+    frame.render_widget(
+        Text::from("e.g. \"7:30 Wake up weekdays\"")
+            .add_modifier(Modifier::DIM)
+            .centered(),
+        panel_layout[2],
+    );
+
+    if state.alarms.is_empty() {
+        frame.render_widget(
+            Text::from("No alarms")
+                .add_modifier(Modifier::ITALIC)
+                .centered(),
+            panel_layout[4],
+        );
+    } else {
+        let lines: Vec<Line> = state
+            .alarms
+            .iter()
+            .enumerate()
+            .map(|(i, alarm)| {
+                let time = if alarm.time.second() == 0 {
+                    alarm.time.format("%H:%M").to_string()
+                } else {
+                    alarm.time.format("%H:%M:%S").to_string()
+                };
+                let line = Line::from(format!(
+                    "{} [{}] {:<8} {:<12.12} {:<11.11}",
+                    if i == state.selected { ">" } else { " " },
+                    if alarm.enabled { "x" } else { " " },
+                    time,
+                    alarm.label,
+                    format_days(alarm.days)
+                ));
+                if alarm.alert.is_some() {
+                    line.fg(Color::Red).add_modifier(Modifier::BOLD)
+                } else if i == state.selected {
+                    line.add_modifier(Modifier::BOLD)
+                } else {
+                    line.add_modifier(Modifier::DIM)
+                }
+            })
+            .collect();
+        frame.render_widget(Text::from(lines).centered(), panel_layout[4]);
+    }
+
+    if let Some(input) = &state.input {
+        input.render(frame, panel_layout[6]);
+    } else if let Some(message) = &state.message {
+        frame.render_widget(
+            Text::from(&message[..])
+                .add_modifier(Modifier::ITALIC)
+                .centered(),
+            panel_layout[6],
+        );
+    } else if ringing(state, now, config).next().is_some() {
+        frame.render_widget(
+            Text::from("[d]: Dismiss  [z]: Snooze")
+                .add_modifier(Modifier::ITALIC)
+                .centered(),
+            panel_layout[6],
+        );
+    } else if let Some(text) = next_alarm_text(state, local_now) {
+        frame.render_widget(
+            Text::from(text).add_modifier(Modifier::ITALIC).centered(),
+            panel_layout[6],
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::time_source::{FakeTimeSource, TimeSource};
+    use crossterm::event::KeyModifiers;
+    use std::time::Duration;
+
+    fn press(state: &mut AlarmState, code: KeyCode, clock: &FakeTimeSource) {
+        handle_key_event(
+            state,
+            &KeyEvent::new(code, KeyModifiers::NONE),
+            clock.local(),
+            &Config::default(),
+        );
+    }
+
+    fn tick(state: &mut AlarmState, clock: &FakeTimeSource) -> bool {
+        tick_alarms(state, clock.instant(), clock.local(), &Config::default())
+    }
+
+    fn add(state: &mut AlarmState, text: &str, clock: &FakeTimeSource) {
+        press(state, KeyCode::Char('a'), clock);
+        for c in text.chars() {
+            press(state, KeyCode::Char(c), clock);
+        }
+        press(state, KeyCode::Enter, clock);
+    }
+
+    #[test]
+    fn parses_alarms() {
+        let alarm = parse_alarm("7:30 Wake up weekdays").unwrap();
+        assert_eq!(alarm.time, NaiveTime::from_hms_opt(7, 30, 0).unwrap());
+        assert_eq!(alarm.label, "Wake up");
+        assert_eq!(alarm.days, WEEKDAYS);
+        assert_eq!(parse_alarm("15:30").unwrap().days, 0);
+        assert_eq!(parse_alarm("15:30:10 Tea").unwrap().label, "Tea");
+        assert_eq!(parse_alarm(""), Err(ParseAlarmError::Empty));
+        assert_eq!(
+            parse_alarm("25:00"),
+            Err(ParseAlarmError::Time("25:00".to_string()))
+        );
+
+        assert_eq!(parse_days("mon,wed"), Ok(0b0000101));
+        assert_eq!(parse_days("Sat-Mon"), Ok(0b1100001));
+        assert_eq!(parse_days("mon-fri"), Ok(WEEKDAYS));
+        assert!(parse_days("someday").is_err());
+        assert_eq!(format_days(0b0000101), "Mon,Wed");
+        assert_eq!(parse_days(&format_days(0b1010100)), Ok(0b1010100));
+    }
+
+    #[test]
+    fn rings_on_the_given_weekdays() {
+        // 2025-01-03 は金曜日
+        let mut clock = FakeTimeSource::at(2025, 1, 3, 7, 0, 0);
+        let mut state = AlarmState::default();
+        add(&mut state, "7:30 Work weekdays", &clock);
+        assert!(!tick(&mut state, &clock));
+        assert_eq!(
+            next_alarm_text(&state, clock.local()).as_deref(),
+            Some("Next alarm: Fri 07:30 Work")
+        );
+
+        clock.advance(Duration::from_secs(30 * 60));
+        assert!(tick(&mut state, &clock));
+        let display = update_alarm(&state, clock.instant(), clock.local(), &Config::default());
+        assert_eq!(display.block_title, "Alarm!");
+        assert_eq!(display.block_content, "Work");
+        press(&mut state, KeyCode::Char('d'), &clock);
+        clock.advance(Duration::from_secs(1));
+        assert!(!tick(&mut state, &clock));
+
+        // 週末は飛ばして月曜日に鳴る
+        let (next, _) = next_alarm(&state, clock.local()).unwrap();
+        assert_eq!(next.to_string(), "2025-01-06 07:30:00");
+        let display = update_alarm(&state, clock.instant(), clock.local(), &Config::default());
+        assert_eq!(display.block_content, "-71:59:59");
+        assert!(state.alarms[0].enabled);
+    }
+
+    #[test]
+    fn one_shot_alarms_snooze_and_turn_off() {
+        let mut clock = FakeTimeSource::at(2025, 1, 1, 15, 29, 59);
+        let mut state = AlarmState::default();
+        add(&mut state, "15:30", &clock);
+        tick(&mut state, &clock);
+        clock.advance(Duration::from_secs(1));
+        assert!(tick(&mut state, &clock));
+        assert!(!state.alarms[0].enabled);
+
+        press(&mut state, KeyCode::Char('z'), &clock);
+        clock.advance(Duration::from_secs(60));
+        assert!(!tick(&mut state, &clock));
+        assert_eq!(
+            next_alarm_text(&state, clock.local()).as_deref(),
+            Some("Next alarm: Wed 15:35")
+        );
+        clock.advance(Duration::from_secs(4 * 60));
+        assert!(tick(&mut state, &clock));
+
+        press(&mut state, KeyCode::Char('d'), &clock);
+        clock.advance(Duration::from_secs(24 * 3600));
+        assert!(!tick(&mut state, &clock));
+        assert_eq!(next_alarm(&state, clock.local()), None);
+    }
+
+    #[test]
+    fn alarms_are_saved_and_loaded() {
+        let clock = FakeTimeSource::default();
+        let path = std::env::temp_dir().join(format!("atclock-alarms-{}.toml", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        let mut state = AlarmState::default();
+        load_alarms(&mut state, path.clone());
+        add(&mut state, "7:30 Wake up mon-fri", &clock);
+        add(&mut state, "22:00:30 Sleep sat,sun", &clock);
+        press(&mut state, KeyCode::Char(' '), &clock);
+
+        let mut loaded = AlarmState::default();
+        load_alarms(&mut loaded, path.clone());
+        assert_eq!(loaded.alarms, state.alarms);
+        assert!(!loaded.alarms[1].enabled);
+        assert_eq!(loaded.alarms[1].days, WEEKENDS);
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn broken_alarms_file_is_not_overwritten() {
+        let clock = FakeTimeSource::default();
+        let path =
+            std::env::temp_dir().join(format!("atclock-broken-alarms-{}.toml", std::process::id()));
+        for text in [
+            "[[alarm]]\ntime = \"7:30\"\n[[alarm]]\ntime = \"25:00\"\n",
+            "[[alarm]\ntime = \"7:30\"\n",
+        ] {
+            std::fs::write(&path, text).unwrap();
+            let mut state = AlarmState::default();
+            load_alarms(&mut state, path.clone());
+            assert!(state.path.is_none());
+            assert!(state.message.as_ref().unwrap().contains("not be saved"));
+            add(&mut state, "9:00 Standup", &clock);
+            assert_eq!(std::fs::read_to_string(&path).unwrap(), text);
+            assert!(state.message.as_ref().unwrap().starts_with("Not saved: "));
+        }
+        std::fs::remove_file(&path).unwrap();
+    }
+}
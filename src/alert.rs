@@ -0,0 +1,138 @@
+use std::time::{Duration, Instant};
+
+use serde::Deserialize;
+
+/// How an expired timer gets the user's attention. Read from the `[alert]` table of the config.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct AlertConfig {
+    /// Ring the terminal bell.
+    pub bell: bool,
+    /// Number of bells to ring; 0 rings until the alert ends.
+    pub bell_count: u32,
+    pub bell_interval_ms: u64,
+    /// Invert the whole screen every other second.
+    pub flash: bool,
+    /// Seconds after which the alert stops by itself; 0 never stops.
+    pub timeout_secs: u64,
+    /// Minutes added by snooze.
+    pub snooze_minutes: u32,
+}
+impl Default for AlertConfig {
+    fn default() -> AlertConfig {
+        AlertConfig {
+            bell: true,
+            bell_count: 0,
+            bell_interval_ms: 250,
+            flash: false,
+            timeout_secs: 0,
+            snooze_minutes: 5,
+        }
+    }
+}
+
+/// A ringing alert, from expiry until it is dismissed or times out.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Alert {
+    started: Instant,
+    last_bell: Option<Instant>,
+    bells: u32,
+    dismissed: bool,
+}
+
+impl Alert {
+    pub fn new(now: Instant) -> Alert {
+        Alert {
+            started: now,
+            last_bell: None,
+            bells: 0,
+            dismissed: false,
+        }
+    }
+
+    /// Acknowledges the alert so that it stops ringing.
+    pub fn dismiss(&mut self) {
+        self.dismissed = true;
+    }
+
+    /// Whether the alert is still ringing, i.e. neither dismissed nor timed out.
+    pub fn is_active(&self, now: Instant, config: &AlertConfig) -> bool {
+        !self.dismissed
+            && (config.timeout_secs == 0
+                || now.saturating_duration_since(self.started)
+                    < Duration::from_secs(config.timeout_secs))
+    }
+
+    /// Returns whether the bell should ring now, and counts it if so.
+    pub fn tick(&mut self, now: Instant, config: &AlertConfig) -> bool {
+        if !config.bell
+            || !self.is_active(now, config)
+            || (config.bell_count != 0 && self.bells >= config.bell_count)
+        {
+            return false;
+        }
+        let interval = Duration::from_millis(config.bell_interval_ms);
+        if self
+            .last_bell
+            .is_some_and(|t| now.saturating_duration_since(t) < interval)
+        {
+            return false;
+        }
+        self.last_bell = Some(now);
+        self.bells += 1;
+        true
+    }
+
+    /// Whether the screen should be inverted now.
+    pub fn is_flashing(&self, now: Instant, config: &AlertConfig) -> bool {
+        config.flash
+            && self.is_active(now, config)
+            && now
+                .saturating_duration_since(self.started)
+                .as_secs()
+                .is_multiple_of(2)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rings_count_times_at_interval() {
+        let config = AlertConfig {
+            bell_count: 3,
+            bell_interval_ms: 1000,
+            ..Default::default()
+        };
+        let start = Instant::now();
+        let mut alert = Alert::new(start);
+        let rings: Vec<u64> = (0..50)
+            .map(|i| i * 100)
+            .filter(|&ms| alert.tick(start + Duration::from_millis(ms), &config))
+            .collect();
+        assert_eq!(rings, vec![0, 1000, 2000]);
+        assert!(alert.is_active(start + Duration::from_secs(100), &config));
+    }
+
+    #[test]
+    fn stops_after_timeout_or_dismiss() {
+        let config = AlertConfig {
+            timeout_secs: 10,
+            flash: true,
+            ..Default::default()
+        };
+        let start = Instant::now();
+        let mut alert = Alert::new(start);
+        assert!(alert.tick(start, &config));
+        assert!(alert.is_flashing(start, &config));
+        assert!(!alert.is_flashing(start + Duration::from_millis(1500), &config));
+        assert!(!alert.tick(start + Duration::from_secs(10), &config));
+        assert!(!alert.is_flashing(start + Duration::from_secs(10), &config));
+
+        let mut alert = Alert::new(start);
+        alert.dismiss();
+        assert!(!alert.tick(start, &config));
+        assert!(!alert.is_active(start, &config));
+    }
+}
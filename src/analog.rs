@@ -12,8 +12,33 @@ pub struct ClockState {
     pub hour_rad: f64,
     pub min_rad: f64,
     pub sec_rad: f64,
+    /// Progress arc drawn along the rim, clockwise from 12 o'clock.
+    pub ring: Option<Ring>,
+    pub face: Face,
+    /// Angles of sunrise and sunset, drawn as an arc along the rim of a 24-hour face.
+    pub daylight: Option<(f64, f64)>,
 }
 
+/// Numerals printed around the dial.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum Face {
+    /// 1 to 12, with 12 at the top.
+    #[default]
+    Twelve,
+    /// 0 to 23, with 0 at the top; the hour hand goes around once a day.
+    TwentyFour,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct Ring {
+    /// Portion of the rim covered, from 0 to 1.
+    pub fraction: f64,
+    pub color: Color,
+}
+
+/// Color of the daylight arc.
+const DAYLIGHT_COLOR: Color = Color::Yellow;
+
 pub fn draw(ctx: &mut Context, area: &Rect, state: &ClockState) {
     ctx.draw(&Circle {
         x: 0.,
@@ -29,13 +54,23 @@ pub fn draw(ctx: &mut Context, area: &Rect, state: &ClockState) {
     //     ctx.print(0., (-(h / 2.).floor() + num as f64) / (h / 2.), num.to_string());
     //     ctx.print(0., ((h / 2.).floor() - num as f64) / (h / 2.), num.to_string());
     // }
-    for num in 1..13 {
+    let (numerals, step) = match state.face {
+        Face::Twelve => (1..13, 1),
+        // 狭いときは偶数の時刻だけを表示する
+        Face::TwentyFour if h < 16. => (0..24, 2),
+        Face::TwentyFour => (0..24, 1),
+    };
+    let per_turn = match state.face {
+        Face::Twelve => 12.,
+        Face::TwentyFour => 24.,
+    };
+    for num in numerals.step_by(step) {
+        let rad = num as f64 * 2. * PI / per_turn;
         // -w/2+1 <= x <= w/2 の範囲のみ正しく表示される
         // 四捨五入の境界を回避するために0.5の代わりに0.4999
-        let x = (num as f64 * PI / 6.).sin() * ((w / 2.).ceil() - 2.5) + 0.4999
-            - (if num >= 10 { 0.5 } else { 0. });
+        let x = rad.sin() * ((w / 2.).ceil() - 2.5) + 0.4999 - (if num >= 10 { 0.5 } else { 0. });
         // -h/2 <= y <= h/2-1 の範囲のみ正しく表示される
-        let y = (num as f64 * PI / 6.).cos() * ((h / 2.).ceil() - 1.5) - 0.4999;
+        let y = rad.cos() * ((h / 2.).ceil() - 1.5) - 0.4999;
         ctx.print(
             x.round() / (w / 2.),
             y.round() / (h / 2.),
@@ -45,6 +80,35 @@ pub fn draw(ctx: &mut Context, area: &Rect, state: &ClockState) {
         );
     }
 
+    if let (Face::TwentyFour, Some((rise, set))) = (state.face, state.daylight) {
+        // 日の入りが日の出より前なら日付をまたぐ
+        let set = if set < rise { set + 2. * PI } else { set };
+        let coords: Vec<(f64, f64)> = (0..=((set - rise).to_degrees().round() as i32))
+            .map(|deg| {
+                let rad = rise + (deg as f64).to_radians();
+                (rad.sin() * 0.95, rad.cos() * 0.95)
+            })
+            .collect();
+        ctx.draw(&canvas::Points {
+            coords: &coords,
+            color: DAYLIGHT_COLOR,
+        });
+    }
+
+    if let Some(ring) = &state.ring {
+        // 点の間隔は 1 度ずつ
+        let coords: Vec<(f64, f64)> = (0..(ring.fraction.clamp(0., 1.) * 360.).round() as i32)
+            .map(|deg| {
+                let rad = deg as f64 * PI / 180.;
+                (rad.sin() * 0.9, rad.cos() * 0.9)
+            })
+            .collect();
+        ctx.draw(&canvas::Points {
+            coords: &coords,
+            color: ring.color,
+        });
+    }
+
     for d in -5..5 {
         ctx.draw(&canvas::Line {
             x1: state.hour_rad.cos() * 0.005 * (d as f64),
@@ -73,3 +137,62 @@ pub fn draw(ctx: &mut Context, area: &Rect, state: &ClockState) {
         });
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ratatui::{Terminal, backend::TestBackend, widgets::canvas::Canvas};
+
+    /// Renders the dial alone at 10:08:30 into a `width` x `height` buffer.
+    fn render(width: u16, height: u16, face: Face, daylight: Option<(f64, f64)>) -> TestBackend {
+        let mut terminal = Terminal::new(TestBackend::new(width, height)).unwrap();
+        let state = ClockState {
+            hour_rad: match face {
+                Face::Twelve => (10. + 8.5 / 60.) * PI / 6.,
+                Face::TwentyFour => (10. + 8.5 / 60.) * PI / 12.,
+            },
+            min_rad: 8.5 * PI / 30.,
+            sec_rad: 30. * PI / 30.,
+            ring: None,
+            face,
+            daylight,
+        };
+        terminal
+            .draw(|frame| {
+                let area = frame.area();
+                frame.render_widget(
+                    Canvas::default()
+                        .x_bounds([-1., 1.])
+                        .y_bounds([-1., 1.])
+                        .paint(|ctx| draw(ctx, &area, &state)),
+                    area,
+                );
+            })
+            .unwrap();
+        terminal.backend().clone()
+    }
+
+    #[test]
+    fn snapshot_dial() {
+        insta::assert_snapshot!("dial_16x8", render(16, 8, Face::Twelve, None));
+        insta::assert_snapshot!("dial_24x12", render(24, 12, Face::Twelve, None));
+        insta::assert_snapshot!("dial_41x20", render(41, 20, Face::Twelve, None));
+    }
+
+    #[test]
+    fn snapshot_dial_24h() {
+        insta::assert_snapshot!("dial_24h_24x12", render(24, 12, Face::TwentyFour, None));
+        insta::assert_snapshot!("dial_24h_41x20", render(41, 20, Face::TwentyFour, None));
+        // 6:30 から 18:45 まで
+        let daylight = Some((6.5 * PI / 12., 18.75 * PI / 12.));
+        insta::assert_snapshot!(
+            "dial_24h_daylight_41x20",
+            render(41, 20, Face::TwentyFour, daylight)
+        );
+        // 12 時間の文字盤には描かない
+        assert_eq!(
+            render(41, 20, Face::Twelve, daylight).buffer(),
+            render(41, 20, Face::Twelve, None).buffer()
+        );
+    }
+}
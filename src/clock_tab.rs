@@ -1,79 +1,634 @@
-use std::f64::consts::PI;
+use std::{collections::HashMap, f64::consts::PI, path::PathBuf};
 
-use chrono::Timelike;
+use chrono::{
+    Datelike, Timelike,
+    format::{Item, StrftimeItems},
+};
+use crossterm::event::{KeyCode, KeyEvent};
 use ratatui::{
     Frame,
     layout::{Constraint, Direction, Layout, Rect},
     style::{Color, Modifier, Style, Stylize},
-    widgets::{Block, BorderType, calendar},
+    text::{Line, Span, Text},
 };
-use time::OffsetDateTime;
+use serde::Deserialize;
+
+use super::config::Config;
+use super::holidays::{self, Country, Holidays};
+use super::ics::{self, Event};
+use super::month_view::{self, MonthView, WeekStart};
+
+/// Settings of the `[clock]` table of the config.
+#[derive(Debug, Default, Clone, Deserialize)]
+#[serde(default)]
+pub struct ClockConfig {
+    /// Use the 24-hour dial and readout; toggled by [h].
+    pub hour24: bool,
+    /// Pattern of the readout's title, `%Y-%m-%d %a` if unset.
+    pub date_format: Option<String>,
+    /// Pattern of the readout, `%I:%M:%S %p` or `%H:%M:%S` if unset.
+    pub time_format: Option<String>,
+}
+
+/// Named readouts accepted in place of a strftime pattern.
+const NAMED_FORMATS: [(&str, &str); 5] = [
+    ("iso8601", "%Y-%m-%dT%H:%M:%S%:z"),
+    ("epoch", "%s"),
+    ("week", "%G-W%V-%u"),
+    ("ordinal", "%Y-%j"),
+    ("swatch", "@%@"),
+];
+
+/// Resolves a named readout to its pattern.
+fn pattern(format: &str) -> &str {
+    NAMED_FORMATS
+        .iter()
+        .find(|(name, _)| *name == format)
+        .map_or(format, |(_, pattern)| pattern)
+}
 
-pub fn update_chrono() -> super::DisplayData {
-    let now = chrono::Local::now();
-    let block_title = format!("{}", now.format("%Y-%m-%d %a"));
-    let block_content = format!("{}", now.format("%I:%M:%S %p"));
+/// Checks that the patterns in the config are valid strftime patterns.
+pub fn check_formats(config: &ClockConfig) -> color_eyre::Result<()> {
+    for format in [&config.date_format, &config.time_format]
+        .into_iter()
+        .flatten()
+    {
+        // %@ は chrono では扱えないので先に取り除いておく
+        let pattern = expand_beats(pattern(format), "");
+        if StrftimeItems::new(&pattern).any(|item| item == Item::Error) {
+            color_eyre::eyre::bail!("invalid format {:?}", format);
+        }
+    }
+    Ok(())
+}
+
+/// Swatch Internet Time: thousandths of a day, counted from midnight in UTC+1.
+fn swatch_beats<Tz: chrono::TimeZone>(now: &chrono::DateTime<Tz>) -> u32 {
+    let t = now.with_timezone(&chrono::Utc);
+    let secs = (t.num_seconds_from_midnight() + 3600) % 86400;
+    secs * 10 / 864
+}
+
+/// Replaces `%@` in a pattern with `beats`, leaving `%%` alone.
+fn expand_beats(pattern: &str, beats: &str) -> String {
+    let mut out = String::with_capacity(pattern.len());
+    let mut chars = pattern.chars();
+    while let Some(c) = chars.next() {
+        match (c, chars.clone().next()) {
+            ('%', Some('@')) => {
+                chars.next();
+                out.push_str(beats);
+            }
+            ('%', Some('%')) => {
+                chars.next();
+                out.push_str("%%");
+            }
+            _ => out.push(c),
+        }
+    }
+    out
+}
+
+/// Formats `now` with a strftime pattern or a named readout, with `%@` for Swatch beats.
+pub fn format_time<Tz: chrono::TimeZone>(now: &chrono::DateTime<Tz>, format: &str) -> String
+where
+    Tz::Offset: std::fmt::Display,
+{
+    let pattern = expand_beats(pattern(format), &format!("{:03}", swatch_beats(now)));
+    now.format(&pattern).to_string()
+}
+
+pub fn update_chrono<Tz: chrono::TimeZone>(
+    now: chrono::DateTime<Tz>,
+    config: &ClockConfig,
+) -> super::DisplayData
+where
+    Tz::Offset: std::fmt::Display,
+{
+    let block_title = format_time(&now, config.date_format.as_deref().unwrap_or("%Y-%m-%d %a"));
+    let time_format = config.time_format.as_deref();
     let sec = now.second() as f64;
     let min = now.minute() as f64 + sec / 60.;
-    let hour = now.hour12().1 as f64 + min / 60.;
-    let analog_state = super::analog::ClockState {
-        sec_rad: sec * PI / 30.,
-        min_rad: min * PI / 30.,
-        hour_rad: hour * PI / 6.,
+    let (block_content, analog_state) = if config.hour24 {
+        let hour = now.hour() as f64 + min / 60.;
+        (
+            format_time(&now, time_format.unwrap_or("%H:%M:%S")),
+            super::analog::ClockState {
+                sec_rad: sec * PI / 30.,
+                min_rad: min * PI / 30.,
+                hour_rad: hour * PI / 12.,
+                ring: None,
+                face: super::analog::Face::TwentyFour,
+                daylight: None,
+            },
+        )
+    } else {
+        let hour = now.hour12().1 as f64 + min / 60.;
+        (
+            format_time(&now, time_format.unwrap_or("%I:%M:%S %p")),
+            super::analog::ClockState {
+                sec_rad: sec * PI / 30.,
+                min_rad: min * PI / 30.,
+                hour_rad: hour * PI / 6.,
+                ring: None,
+                face: super::analog::Face::Twelve,
+                daylight: None,
+            },
+        )
     };
     super::DisplayData {
         block_title,
         block_content,
+        content_color: Color::Reset,
         analog_state,
     }
 }
 
-pub fn render_panel(frame: &mut Frame, panel_area: Rect) {
-    let panel_area = Layout::default()
-        .direction(Direction::Vertical)
-        .constraints(vec![
-            Constraint::Min(0),
-            Constraint::Length(10),
-            Constraint::Min(0),
+/// Settings of the `[calendar]` table of the config.
+#[derive(Debug, Default, Clone, Deserialize)]
+#[serde(default)]
+pub struct CalendarConfig {
+    /// iCalendar files whose events are shown on the calendar.
+    pub ics: Vec<PathBuf>,
+    /// Countries whose public holidays are highlighted, e.g. `jp` or `de`.
+    pub holidays: Vec<String>,
+    pub week_start: WeekStart,
+    /// Show ISO week numbers to the left of each week.
+    pub week_numbers: bool,
+}
+
+/// Which months the calendar shows, and how they are laid out.
+#[derive(Debug, Default)]
+pub struct CalendarState {
+    /// Months between the current month and the one shown; 0 follows today.
+    offset: i32,
+    view: CalendarView,
+    events: Vec<Event>,
+    holidays: Holidays,
+    /// Feedback for the last load, e.g. a parse error.
+    message: Option<String>,
+}
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+enum CalendarView {
+    #[default]
+    Month,
+    /// The shown month and the ones before and after it.
+    ThreeMonths,
+    /// January to December of the shown year.
+    Year,
+}
+impl CalendarView {
+    fn next(self) -> CalendarView {
+        match self {
+            CalendarView::Month => CalendarView::ThreeMonths,
+            CalendarView::ThreeMonths => CalendarView::Year,
+            CalendarView::Year => CalendarView::Month,
+        }
+    }
+}
+
+/// Color of the days with events.
+const EVENT_COLOR: Color = Color::LightMagenta;
+/// Color of holidays.
+const HOLIDAY_COLOR: Color = Color::LightRed;
+
+pub fn handle_key_event(config: &mut ClockConfig, calendar: &mut CalendarState, key: &KeyEvent) {
+    match (key.modifiers, key.code) {
+        (_, KeyCode::Char('h')) => config.hour24 = !config.hour24,
+        (_, KeyCode::Left) => calendar.offset -= 1,
+        (_, KeyCode::Right) => calendar.offset += 1,
+        (_, KeyCode::Up) => calendar.offset -= 12,
+        (_, KeyCode::Down) => calendar.offset += 12,
+        (_, KeyCode::Char('t')) => calendar.offset = 0,
+        (_, KeyCode::Char('v')) => calendar.view = calendar.view.next(),
+        _ => {}
+    }
+}
+
+/// Reads the events of the files in `config`, with `~` standing for the home directory.
+pub fn load_events(state: &mut CalendarState, config: &CalendarConfig) {
+    state.events.clear();
+    state.message = None;
+    for path in &config.ics {
+        let path = match (path.strip_prefix("~"), dirs::home_dir()) {
+            (Ok(rest), Some(home)) => home.join(rest),
+            _ => path.clone(),
+        };
+        let events = std::fs::read_to_string(&path)
+            .map_err(|e| e.to_string())
+            .and_then(|text| ics::parse_ics(&text).map_err(|e| e.to_string()));
+        match events {
+            Ok(events) => state.events.extend(events),
+            Err(e) => state.message = Some(format!("{}: {e}", path.display())),
+        }
+    }
+    state.events.sort_by_key(|e| e.start);
+}
+
+/// Reads the countries in `config`, and the days of the holidays file at `path` if there is one.
+pub fn load_holidays(state: &mut CalendarState, config: &CalendarConfig, path: PathBuf) {
+    state.holidays.countries.clear();
+    for code in &config.holidays {
+        match Country::from_code(code) {
+            Some(country) => state.holidays.countries.push(country),
+            None => state.message = Some(format!("Unknown holiday country: {code}")),
+        }
+    }
+    state.holidays.special = match std::fs::read_to_string(&path) {
+        Ok(text) => holidays::parse_holidays(&text).unwrap_or_else(|e| {
+            state.message = Some(format!("{}: {e}", path.display()));
+            vec![]
+        }),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => vec![],
+        Err(e) => {
+            state.message = Some(format!("Failed to read {}: {e}", path.display()));
+            vec![]
+        }
+    };
+}
+
+/// Events of the day of `now` that have not ended yet.
+fn todays_events(events: &[Event], now: chrono::NaiveDateTime) -> impl Iterator<Item = &Event> {
+    events.iter().filter(move |e| {
+        e.days().any(|d| d == now.date()) && (e.all_day || e.end.max(e.start) >= now)
+    })
+}
+
+/// The next event with a start time after `now`.
+fn next_event(events: &[Event], now: chrono::NaiveDateTime) -> Option<&Event> {
+    events.iter().find(|e| !e.all_day && e.start > now)
+}
+
+/// Time left until `start`, e.g. `1:05:00`, or `2d 3h` a day or more ahead.
+fn format_countdown(left: chrono::TimeDelta) -> String {
+    if left.num_days() > 0 {
+        format!("{}d {}h", left.num_days(), left.num_hours() % 24)
+    } else {
+        format!(
+            "{}:{:02}:{:02}",
+            left.num_hours(),
+            left.num_minutes() % 60,
+            left.num_seconds() % 60
+        )
+    }
+}
+
+/// The first day of the month `offset` months after the month of `today`.
+fn shift_month(today: chrono::NaiveDate, offset: i32) -> chrono::NaiveDate {
+    let first = today.with_day(1).unwrap();
+    let months = chrono::Months::new(offset.unsigned_abs());
+    if offset < 0 {
+        first.checked_sub_months(months)
+    } else {
+        first.checked_add_months(months)
+    }
+    .unwrap_or(first)
+}
+
+/// Months to show, falling back to smaller views until they fit in `area`.
+///
+/// Returns the first day of each month and the number of columns to lay them out in.
+fn months_to_show(
+    calendar: &CalendarState,
+    today: chrono::NaiveDate,
+    area: Rect,
+    month_width: u16,
+) -> (Vec<chrono::NaiveDate>, usize) {
+    let shown = shift_month(today, calendar.offset);
+    let max_cols = std::cmp::max(area.width / month_width, 1) as usize;
+    let mut view = calendar.view;
+    loop {
+        let months: Vec<_> = match view {
+            CalendarView::Month => vec![shown],
+            CalendarView::ThreeMonths => (-1..=1).map(|i| shift_month(shown, i)).collect(),
+            CalendarView::Year => (0..12)
+                .map(|i| shown.with_month(1).unwrap() + chrono::Months::new(i))
+                .collect(),
+        };
+        let cols = std::cmp::min(months.len(), max_cols);
+        let rows = months.len().div_ceil(cols) as u16;
+        if view == CalendarView::Month || rows * month_view::HEIGHT <= area.height.saturating_sub(3)
+        {
+            return (months, cols);
+        }
+        view = match view {
+            CalendarView::Year => CalendarView::ThreeMonths,
+            _ => CalendarView::Month,
+        };
+    }
+}
+
+/// Renders the calendar, with the sun's times, the events of today and the next alarm below it.
+///
+/// `now` is the local time, which events and the sun's times are given in.
+pub fn render_panel(
+    frame: &mut Frame,
+    panel_area: Rect,
+    state: &CalendarState,
+    config: &Config,
+    today: chrono::NaiveDate,
+    now: chrono::NaiveDateTime,
+    next_alarm: Option<String>,
+) {
+    let mut lines: Vec<Line> = match &config.location {
+        Some(location) => {
+            let times = super::sun::sun_times(now.date(), location);
+            super::sun::describe(&times, &chrono::Local)
+                .into_iter()
+                .map(Line::from)
+                .collect()
+        }
+        None => vec![],
+    };
+    lines.extend(state.holidays.on(today).into_iter().map(|name| {
+        Line::from(format!("Today: {name}"))
+            .fg(HOLIDAY_COLOR)
+            .add_modifier(Modifier::BOLD)
+    }));
+    lines.extend(todays_events(&state.events, now).take(4).map(|e| {
+        let time = match e.all_day {
+            true => "All day".to_string(),
+            false => e.start.format("%H:%M").to_string(),
+        };
+        Line::from(vec![
+            Span::from(time).fg(EVENT_COLOR),
+            Span::from(format!(" {}", e.summary)),
         ])
-        .split(panel_area)[1];
-    let panel_area = Layout::default()
-        .direction(Direction::Horizontal)
+    }));
+    if let Some(e) = next_event(&state.events, now) {
+        lines.push(
+            Line::from(format!(
+                "Next: {} in {}",
+                e.summary,
+                format_countdown(e.start - now)
+            ))
+            .add_modifier(Modifier::ITALIC),
+        );
+    }
+    if let Some(message) = &state.message {
+        lines.push(Line::from(&message[..]).add_modifier(Modifier::ITALIC));
+    }
+    let events_h = if lines.is_empty() {
+        0
+    } else {
+        lines.len() as u16 + 1
+    };
+    let alarm_h = if next_alarm.is_some() { 2 } else { 0 };
+
+    let months_area = Rect {
+        height: panel_area.height.saturating_sub(events_h + alarm_h),
+        ..panel_area
+    };
+    let month_width = month_view::width(config.calendar.week_numbers);
+    let (months, cols) = months_to_show(state, today, months_area, month_width);
+    let rows = months.len().div_ceil(cols);
+    let mut styles = HashMap::new();
+    let first = months[0];
+    let last = months
+        .last()
+        .unwrap()
+        .checked_add_months(chrono::Months::new(1))
+        .unwrap_or(chrono::NaiveDate::MAX);
+    for year in first.year()..=last.year() {
+        for (day, _) in state.holidays.in_year(year) {
+            styles.insert(
+                day,
+                Style::default()
+                    .fg(HOLIDAY_COLOR)
+                    .add_modifier(Modifier::BOLD),
+            );
+        }
+    }
+    for e in &state.events {
+        if e.start.date() < last && e.end.date() >= first {
+            for day in e.days() {
+                // 祝日の色はそのままにして下線だけを付ける
+                let style = styles
+                    .entry(day)
+                    .or_insert(Style::default().fg(EVENT_COLOR));
+                *style = style.add_modifier(Modifier::UNDERLINED);
+            }
+        }
+    }
+    styles.insert(
+        today,
+        Style::default()
+            .fg(Color::LightBlue)
+            .add_modifier(Modifier::BOLD)
+            .add_modifier(Modifier::ITALIC)
+            .add_modifier(Modifier::REVERSED),
+    );
+    let v_layout = Layout::default()
+        .direction(Direction::Vertical)
         .constraints(vec![
             Constraint::Min(0),
-            Constraint::Length(24),
+            Constraint::Length(1),
+            Constraint::Length(1),
+            Constraint::Length(1),
+            Constraint::Length(rows as u16 * month_view::HEIGHT),
+            Constraint::Length(events_h),
+            Constraint::Length(alarm_h),
             Constraint::Min(0),
         ])
-        .split(panel_area)[1];
-
+        .split(panel_area);
     frame.render_widget(
-        calendar::Monthly::new(
-            OffsetDateTime::now_local().unwrap().date(),
-            calendar::CalendarEventStore::today(
-                Style::default()
-                    .fg(Color::LightBlue)
-                    .add_modifier(Modifier::BOLD)
-                    .add_modifier(Modifier::ITALIC)
-                    .add_modifier(Modifier::REVERSED),
-            ),
-        )
-        .default_style(Style::default().remove_modifier(Modifier::DIM))
-        .block(
-            Block::bordered()
-                .border_type(BorderType::Rounded)
-                .add_modifier(Modifier::DIM),
-        )
-        .show_month_header(
-            Style::default()
-                .remove_modifier(Modifier::DIM)
-                .add_modifier(Modifier::BOLD),
-        )
-        .show_weekdays_header(
-            Style::default()
-                .add_modifier(Modifier::DIM)
-                .add_modifier(Modifier::ITALIC),
-        )
-        .show_surrounding(Style::default().add_modifier(Modifier::DIM)),
-        panel_area,
+        Text::from("←→: Month  ↑↓: Year  [t]: Today")
+            .add_modifier(Modifier::DIM)
+            .centered(),
+        v_layout[1],
+    );
+    frame.render_widget(
+        Text::from("[v]: View  [h]: 12 / 24-hour dial")
+            .add_modifier(Modifier::DIM)
+            .centered(),
+        v_layout[2],
     );
+    if !lines.is_empty() {
+        frame.render_widget(
+            Text::from(lines).centered(),
+            v_layout[5].offset(ratatui::layout::Offset { x: 0, y: 1 }),
+        );
+    }
+    if let Some(text) = next_alarm {
+        frame.render_widget(
+            Text::from(text).add_modifier(Modifier::ITALIC).centered(),
+            v_layout[6].rows().nth(1).unwrap_or_default(),
+        );
+    }
+
+    let row_areas = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints(vec![Constraint::Length(month_view::HEIGHT); rows])
+        .split(v_layout[4]);
+    for (row, row_area) in row_areas.iter().enumerate() {
+        let cells = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints(
+                std::iter::once(Constraint::Min(0))
+                    .chain(std::iter::repeat_n(Constraint::Length(month_width), cols))
+                    .chain(std::iter::once(Constraint::Min(0))),
+            )
+            .split(*row_area);
+        for (col, &month) in months[row * cols..].iter().take(cols).enumerate() {
+            frame.render_widget(
+                MonthView {
+                    month,
+                    styles: &styles,
+                    week_start: config.calendar.week_start,
+                    week_numbers: config.calendar.week_numbers,
+                },
+                cells[col + 1],
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::time_source::{FakeTimeSource, TimeSource};
+
+    #[test]
+    fn hands_at_three_oclock() {
+        let display = update_chrono(
+            FakeTimeSource::at(2025, 1, 1, 15, 0, 0).local(),
+            &ClockConfig::default(),
+        );
+        assert_eq!(display.block_content, "03:00:00 PM");
+        assert_eq!(display.block_title, "2025-01-01 Wed");
+        assert!((display.analog_state.hour_rad - PI / 2.).abs() < 1e-9);
+        assert!(display.analog_state.min_rad.abs() < 1e-9);
+        assert!(display.analog_state.sec_rad.abs() < 1e-9);
+    }
+
+    #[test]
+    fn hour_hand_moves_with_minutes() {
+        let display = update_chrono(
+            FakeTimeSource::at(2025, 1, 1, 9, 30, 15).local(),
+            &ClockConfig::default(),
+        );
+        // 9:30:15 → 短針は 9.5 時間分 + 15 秒
+        let hour = 9. + (30. + 15. / 60.) / 60.;
+        assert!((display.analog_state.hour_rad - hour * PI / 6.).abs() < 1e-9);
+        assert!((display.analog_state.min_rad - (30.25) * PI / 30.).abs() < 1e-9);
+        assert!((display.analog_state.sec_rad - 15. * PI / 30.).abs() < 1e-9);
+    }
+
+    #[test]
+    fn hour_hand_goes_around_once_a_day() {
+        let mut config = ClockConfig::default();
+        handle_key_event(
+            &mut config,
+            &mut CalendarState::default(),
+            &KeyEvent::from(KeyCode::Char('h')),
+        );
+        let display = update_chrono(FakeTimeSource::at(2025, 1, 1, 18, 0, 0).local(), &config);
+        assert_eq!(display.block_content, "18:00:00");
+        assert_eq!(display.analog_state.face, crate::analog::Face::TwentyFour);
+        assert!((display.analog_state.hour_rad - 1.5 * PI).abs() < 1e-9);
+    }
+
+    #[test]
+    fn named_formats() {
+        use chrono::TimeZone;
+        let now = chrono::FixedOffset::east_opt(9 * 3600)
+            .unwrap()
+            .with_ymd_and_hms(2025, 1, 1, 9, 30, 0)
+            .unwrap();
+        assert_eq!(format_time(&now, "iso8601"), "2025-01-01T09:30:00+09:00");
+        assert_eq!(format_time(&now, "epoch"), "1735691400");
+        assert_eq!(format_time(&now, "week"), "2025-W01-3");
+        assert_eq!(format_time(&now, "ordinal"), "2025-001");
+        // 00:30 UTC は BMT で 01:30
+        assert_eq!(format_time(&now, "swatch"), "@062");
+        assert_eq!(format_time(&now, "%H%%@ %@"), "09%@ 062");
+    }
+
+    #[test]
+    fn rejects_invalid_formats() {
+        let config = ClockConfig {
+            date_format: Some("%A %e %B".to_string()),
+            time_format: Some("swatch".to_string()),
+            ..Default::default()
+        };
+        assert!(check_formats(&config).is_ok());
+        let config = ClockConfig {
+            time_format: Some("%H:%Q".to_string()),
+            ..Default::default()
+        };
+        assert!(check_formats(&config).is_err());
+    }
+
+    #[test]
+    fn lists_todays_events() {
+        let at = |d, h, m| {
+            chrono::NaiveDate::from_ymd_opt(2025, 1, d)
+                .unwrap()
+                .and_hms_opt(h, m, 0)
+                .unwrap()
+        };
+        let event = |summary: &str, start, end, all_day| Event {
+            summary: summary.to_string(),
+            start,
+            end,
+            all_day,
+        };
+        let events = [
+            event("Holiday", at(1, 0, 0), at(2, 0, 0), true),
+            event("Standup", at(1, 9, 0), at(1, 9, 15), false),
+            event("Lunch", at(1, 12, 0), at(1, 13, 0), false),
+            event("Dentist", at(3, 10, 0), at(3, 10, 0), false),
+        ];
+        let summaries = |now| {
+            todays_events(&events, now)
+                .map(|e| &e.summary[..])
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(summaries(at(1, 12, 30)), ["Holiday", "Lunch"]);
+        assert_eq!(summaries(at(2, 8, 0)), Vec::<&str>::new());
+        let now = at(1, 12, 30);
+        let next = next_event(&events, now).unwrap();
+        assert_eq!(next.summary, "Dentist");
+        assert_eq!(format_countdown(next.start - now), "1d 21h");
+        assert_eq!(format_countdown(at(1, 14, 0) - now), "1:30:00");
+    }
+
+    #[test]
+    fn pages_through_months() {
+        let today = chrono::NaiveDate::from_ymd_opt(2025, 1, 31).unwrap();
+        let date = |y, m| chrono::NaiveDate::from_ymd_opt(y, m, 1).unwrap();
+        let mut calendar = CalendarState::default();
+        let press = |calendar: &mut CalendarState, code| {
+            handle_key_event(&mut ClockConfig::default(), calendar, &KeyEvent::from(code))
+        };
+        press(&mut calendar, KeyCode::Left);
+        press(&mut calendar, KeyCode::Down);
+        press(&mut calendar, KeyCode::Char('v'));
+        let area = Rect::new(0, 0, 80, 40);
+        assert_eq!(
+            months_to_show(&calendar, today, area, 24),
+            (vec![date(2025, 11), date(2025, 12), date(2026, 1)], 3)
+        );
+
+        // 収まらない場合は小さい表示にする
+        press(&mut calendar, KeyCode::Char('v'));
+        assert_eq!(months_to_show(&calendar, today, area, 24).0.len(), 3);
+        let area = Rect::new(0, 0, 100, 40);
+        assert_eq!(
+            months_to_show(&calendar, today, area, 24).0[0],
+            date(2025, 1)
+        );
+        assert_eq!(months_to_show(&calendar, today, area, 24).1, 4);
+        let area = Rect::new(0, 0, 20, 20);
+        assert_eq!(
+            months_to_show(&calendar, today, area, 24),
+            (vec![date(2025, 12)], 1)
+        );
+
+        press(&mut calendar, KeyCode::Char('t'));
+        assert_eq!(
+            months_to_show(&calendar, today, area, 24).0,
+            vec![date(2025, 1)]
+        );
+    }
 }
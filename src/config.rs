@@ -0,0 +1,62 @@
+use std::path::{Path, PathBuf};
+
+use color_eyre::eyre::WrapErr;
+use serde::{Deserialize, Serialize, de::DeserializeOwned};
+
+/// Settings read from `config.toml` in [`dir`].
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct Config {
+    pub alert: super::alert::AlertConfig,
+    pub clock: super::clock_tab::ClockConfig,
+    pub calendar: super::clock_tab::CalendarConfig,
+    pub timer: super::timer_tab::TimerConfig,
+    pub pomodoro: super::pomodoro_tab::PomodoroConfig,
+    pub world: super::world_tab::WorldConfig,
+    /// Where sunrise and sunset are computed for; they are not shown without it.
+    pub location: Option<super::sun::Location>,
+}
+
+/// Reads `config.toml`, falling back to the defaults if there is none.
+pub fn load_config() -> color_eyre::Result<Config> {
+    match dir() {
+        Some(dir) => {
+            let path = dir.join("config.toml");
+            let config: Config =
+                load(&path).wrap_err_with(|| format!("failed to read {}", path.display()))?;
+            super::clock_tab::check_formats(&config.clock)
+                .wrap_err_with(|| format!("failed to read {}", path.display()))?;
+            super::pomodoro_tab::check_config(&config.pomodoro)
+                .wrap_err_with(|| format!("failed to read {}", path.display()))?;
+            if let Some(location) = &config.location {
+                super::sun::check_location(location)
+                    .wrap_err_with(|| format!("failed to read {}", path.display()))?;
+            }
+            Ok(config)
+        }
+        None => Ok(Config::default()),
+    }
+}
+
+/// Directory holding atclock's files, e.g. `~/.config/atclock` on Linux.
+pub fn dir() -> Option<PathBuf> {
+    dirs::config_dir().map(|d| d.join("atclock"))
+}
+
+/// Reads a TOML file, or returns the default value if the file does not exist yet.
+pub fn load<T: DeserializeOwned + Default>(path: &Path) -> color_eyre::Result<T> {
+    match std::fs::read_to_string(path) {
+        Ok(s) => Ok(toml::from_str(&s)?),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(T::default()),
+        Err(e) => Err(e.into()),
+    }
+}
+
+/// Writes a TOML file, creating its directory if needed.
+pub fn save<T: Serialize>(path: &Path, value: &T) -> color_eyre::Result<()> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    std::fs::write(path, toml::to_string(value)?)?;
+    Ok(())
+}
@@ -0,0 +1,500 @@
+use chrono::{Datelike, NaiveDate, Weekday};
+
+/// Countries whose public holidays are built in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Country {
+    /// Japanese national holidays, with substitute and citizens' holidays.
+    Jp,
+    /// Bank holidays of England and Wales.
+    Gb,
+    /// Nationwide holidays of Germany.
+    De,
+    Fr,
+}
+impl Country {
+    pub fn from_code(code: &str) -> Option<Country> {
+        match &code.to_ascii_lowercase()[..] {
+            "jp" => Some(Country::Jp),
+            "gb" | "uk" => Some(Country::Gb),
+            "de" => Some(Country::De),
+            "fr" => Some(Country::Fr),
+            _ => None,
+        }
+    }
+}
+
+/// A day from the holidays file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SpecialDay {
+    /// `None` for a day that comes every year.
+    year: Option<i32>,
+    month: u32,
+    day: u32,
+    name: String,
+}
+
+/// Public holidays of some countries, plus the days of the holidays file.
+#[derive(Debug, Default)]
+pub struct Holidays {
+    pub countries: Vec<Country>,
+    pub special: Vec<SpecialDay>,
+}
+impl Holidays {
+    /// Holidays of `year`, sorted by date. A day may appear more than once.
+    pub fn in_year(&self, year: i32) -> Vec<(NaiveDate, String)> {
+        let mut days: Vec<(NaiveDate, String)> = self
+            .countries
+            .iter()
+            .flat_map(|&country| builtin(country, year))
+            // chrono で表せない年などの存在しない日は除く
+            .filter_map(|(date, name)| Some((date?, name.to_string())))
+            .collect();
+        days.extend(self.special.iter().filter_map(|s| {
+            if s.year.is_some_and(|y| y != year) {
+                return None;
+            }
+            NaiveDate::from_ymd_opt(year, s.month, s.day).map(|d| (d, s.name.clone()))
+        }));
+        days.sort();
+        days
+    }
+
+    /// Names of the holidays on `date`.
+    pub fn on(&self, date: NaiveDate) -> Vec<String> {
+        self.in_year(date.year())
+            .into_iter()
+            .filter(|(d, _)| *d == date)
+            .map(|(_, name)| name)
+            .collect()
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum ParseHolidaysError {
+    /// A line that could not be read, with its line number.
+    Invalid(usize, String),
+}
+impl std::fmt::Display for ParseHolidaysError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ParseHolidaysError::Invalid(line, s) => write!(f, "line {line}: invalid day: {s}"),
+        }
+    }
+}
+impl std::error::Error for ParseHolidaysError {}
+
+/// Reads a holidays file: one `YYYY-MM-DD name` or yearly `MM-DD name` per line, with `#`
+/// starting a comment.
+pub fn parse_holidays(text: &str) -> Result<Vec<SpecialDay>, ParseHolidaysError> {
+    let mut days = vec![];
+    for (i, line) in text.lines().enumerate() {
+        let line = line.split('#').next().unwrap_or_default().trim();
+        if line.is_empty() {
+            continue;
+        }
+        let invalid = || ParseHolidaysError::Invalid(i + 1, line.to_string());
+        let (date, name) = line.split_once(char::is_whitespace).unwrap_or((line, ""));
+        let parts: Vec<&str> = date.split('-').collect();
+        let numbers: Vec<i32> = parts
+            .iter()
+            .map(|p| p.parse().map_err(|_| invalid()))
+            .collect::<Result<_, _>>()?;
+        let (year, month, day) = match numbers[..] {
+            [y, m, d] => (Some(y), m as u32, d as u32),
+            [m, d] => (None, m as u32, d as u32),
+            _ => return Err(invalid()),
+        };
+        // 閏日も毎年の日として書けるように 2000 年で確かめる
+        if NaiveDate::from_ymd_opt(year.unwrap_or(2000), month, day).is_none() {
+            return Err(invalid());
+        }
+        days.push(SpecialDay {
+            year,
+            month,
+            day,
+            name: name.trim().to_string(),
+        });
+    }
+    Ok(days)
+}
+
+fn date(year: i32, month: u32, day: u32) -> Option<NaiveDate> {
+    NaiveDate::from_ymd_opt(year, month, day)
+}
+
+/// The `n`th `weekday` of a month, or the last one if `n` is 0.
+fn nth_weekday(year: i32, month: u32, weekday: Weekday, n: u8) -> Option<NaiveDate> {
+    match n {
+        0 => {
+            let next = date(year, month + 1, 1).or_else(|| date(year + 1, 1, 1));
+            let last = next?.pred_opt()?;
+            let back =
+                (last.weekday().num_days_from_monday() + 7 - weekday.num_days_from_monday()) % 7;
+            Some(last - chrono::TimeDelta::days(back as i64))
+        }
+        n => NaiveDate::from_weekday_of_month_opt(year, month, weekday, n),
+    }
+}
+
+/// Easter Sunday in the Gregorian calendar (the anonymous Gregorian algorithm).
+fn easter(year: i32) -> Option<NaiveDate> {
+    let a = year % 19;
+    let b = year / 100;
+    let c = year % 100;
+    let d = b / 4;
+    let e = b % 4;
+    let f = (b + 8) / 25;
+    let g = (b - f + 1) / 3;
+    let h = (19 * a + b - d - g + 15) % 30;
+    let i = c / 4;
+    let k = c % 4;
+    let l = (32 + 2 * e + 2 * i - h - k) % 7;
+    let m = (a + 11 * h + 22 * l) / 451;
+    let month = (h + l - 7 * m + 114) / 31;
+    let day = (h + l - 7 * m + 114) % 31 + 1;
+    date(year, month as u32, day as u32)
+}
+
+/// Day of the March or September equinox in Japan, or `None` outside 1980 to 2099 where the
+/// formula is not valid.
+fn equinox(year: i32, base: f64) -> Option<u32> {
+    if !(1980..=2099).contains(&year) {
+        return None;
+    }
+    let y = (year - 1980) as f64;
+    Some((base + 0.242194 * y - (y / 4.).floor()).floor() as u32)
+}
+
+/// Moves a holiday on a weekend to the following Monday.
+fn to_monday(day: NaiveDate) -> Option<NaiveDate> {
+    match day.weekday() {
+        Weekday::Sat => day.checked_add_days(chrono::Days::new(2)),
+        Weekday::Sun => day.checked_add_days(chrono::Days::new(1)),
+        _ => Some(day),
+    }
+}
+
+/// Holidays of `country` in `year`; days that do not exist are `None`.
+fn builtin(country: Country, year: i32) -> Vec<(Option<NaiveDate>, &'static str)> {
+    let easter = easter(year);
+    let after_easter = |days| easter?.checked_add_signed(chrono::TimeDelta::days(days));
+    match country {
+        Country::Jp => japan(year)
+            .into_iter()
+            .map(|(date, name)| (Some(date), name))
+            .collect(),
+        Country::Gb => {
+            let christmas = date(year, 12, 25).and_then(to_monday);
+            // 12/26 が週末か、クリスマスの振替と重なるときはその次の平日
+            let boxing = match date(year, 12, 26).and_then(to_monday) {
+                d if d == christmas => d.and_then(|d| d.succ_opt()),
+                d => d,
+            };
+            vec![
+                (date(year, 1, 1).and_then(to_monday), "New Year's Day"),
+                (after_easter(-2), "Good Friday"),
+                (after_easter(1), "Easter Monday"),
+                (
+                    nth_weekday(year, 5, Weekday::Mon, 1),
+                    "Early May bank holiday",
+                ),
+                (nth_weekday(year, 5, Weekday::Mon, 0), "Spring bank holiday"),
+                (nth_weekday(year, 8, Weekday::Mon, 0), "Summer bank holiday"),
+                (christmas, "Christmas Day"),
+                (boxing, "Boxing Day"),
+            ]
+        }
+        Country::De => vec![
+            (date(year, 1, 1), "Neujahr"),
+            (after_easter(-2), "Karfreitag"),
+            (after_easter(1), "Ostermontag"),
+            (date(year, 5, 1), "Tag der Arbeit"),
+            (after_easter(39), "Christi Himmelfahrt"),
+            (after_easter(50), "Pfingstmontag"),
+            (date(year, 10, 3), "Tag der Deutschen Einheit"),
+            (date(year, 12, 25), "1. Weihnachtstag"),
+            (date(year, 12, 26), "2. Weihnachtstag"),
+        ],
+        Country::Fr => vec![
+            (date(year, 1, 1), "Jour de l'an"),
+            (after_easter(1), "Lundi de Pâques"),
+            (date(year, 5, 1), "Fête du Travail"),
+            (date(year, 5, 8), "Victoire 1945"),
+            (after_easter(39), "Ascension"),
+            (after_easter(50), "Lundi de Pentecôte"),
+            (date(year, 7, 14), "Fête nationale"),
+            (date(year, 8, 15), "Assomption"),
+            (date(year, 11, 1), "Toussaint"),
+            (date(year, 11, 11), "Armistice 1918"),
+            (date(year, 12, 25), "Noël"),
+        ],
+    }
+}
+
+/// Japanese national holidays, following the changes to the law since 1949, including the
+/// one-off holidays of the imperial family and the moves for the 2020 Olympics.
+fn japan(year: i32) -> Vec<(NaiveDate, &'static str)> {
+    if year < 1949 {
+        return vec![];
+    }
+    let since = |from: i32| year >= from;
+    let between = |from: i32, to: i32| (from..=to).contains(&year);
+    // 東京オリンピックの年は日付が移された
+    let olympic = |moved: [(i32, u32, u32); 2], usual: Option<NaiveDate>| match moved
+        .iter()
+        .find(|(y, _, _)| *y == year)
+    {
+        Some(&(y, m, d)) => date(y, m, d),
+        None => usual,
+    };
+    let days = [
+        (date(year, 1, 1), "元日"),
+        (
+            if since(2000) {
+                nth_weekday(year, 1, Weekday::Mon, 2)
+            } else {
+                date(year, 1, 15)
+            },
+            "成人の日",
+        ),
+        (date(year, 2, 11).filter(|_| since(1967)), "建国記念の日"),
+        (
+            if year <= 1988 {
+                date(year, 4, 29)
+            } else if year <= 2018 {
+                date(year, 12, 23)
+            } else {
+                date(year, 2, 23).filter(|_| since(2020))
+            },
+            "天皇誕生日",
+        ),
+        (
+            equinox(year, 20.8431).and_then(|d| date(year, 3, d)),
+            "春分の日",
+        ),
+        (
+            date(year, 4, 29).filter(|_| between(1989, 2006)),
+            "みどりの日",
+        ),
+        (date(year, 4, 29).filter(|_| since(2007)), "昭和の日"),
+        (date(year, 5, 3), "憲法記念日"),
+        (date(year, 5, 4).filter(|_| since(2007)), "みどりの日"),
+        (date(year, 5, 5), "こどもの日"),
+        (
+            olympic(
+                [(2020, 7, 23), (2021, 7, 22)],
+                if since(2003) {
+                    nth_weekday(year, 7, Weekday::Mon, 3)
+                } else {
+                    date(year, 7, 20).filter(|_| since(1996))
+                },
+            ),
+            "海の日",
+        ),
+        (
+            olympic(
+                [(2020, 8, 10), (2021, 8, 8)],
+                date(year, 8, 11).filter(|_| since(2016)),
+            ),
+            "山の日",
+        ),
+        (
+            if since(2003) {
+                nth_weekday(year, 9, Weekday::Mon, 3)
+            } else {
+                date(year, 9, 15).filter(|_| since(1966))
+            },
+            "敬老の日",
+        ),
+        (
+            equinox(year, 23.2488).and_then(|d| date(year, 9, d)),
+            "秋分の日",
+        ),
+        (
+            if since(2000) {
+                nth_weekday(year, 10, Weekday::Mon, 2).filter(|_| year <= 2019)
+            } else {
+                date(year, 10, 10).filter(|_| since(1966))
+            },
+            "体育の日",
+        ),
+        (
+            olympic(
+                [(2020, 7, 24), (2021, 7, 23)],
+                nth_weekday(year, 10, Weekday::Mon, 2).filter(|_| since(2020)),
+            ),
+            "スポーツの日",
+        ),
+        (date(year, 11, 3), "文化の日"),
+        (date(year, 11, 23), "勤労感謝の日"),
+        // 皇室の行事による一度きりの祝日
+        (
+            date(1959, 4, 10).filter(|_| year == 1959),
+            "皇太子明仁親王の結婚の儀",
+        ),
+        (
+            date(1989, 2, 24).filter(|_| year == 1989),
+            "昭和天皇の大喪の礼",
+        ),
+        (
+            date(1990, 11, 12).filter(|_| year == 1990),
+            "即位礼正殿の儀",
+        ),
+        (
+            date(1993, 6, 9).filter(|_| year == 1993),
+            "皇太子徳仁親王の結婚の儀",
+        ),
+        (date(2019, 5, 1).filter(|_| year == 2019), "天皇の即位の日"),
+        (
+            date(2019, 10, 22).filter(|_| year == 2019),
+            "即位礼正殿の儀",
+        ),
+    ];
+    let mut days: Vec<(NaiveDate, &str)> = days
+        .into_iter()
+        .filter_map(|(date, name)| Some((date?, name)))
+        .collect();
+    days.sort();
+    let is_holiday = |d: NaiveDate, days: &[(NaiveDate, &str)]| days.iter().any(|(h, _)| *h == d);
+    let mut extra = vec![];
+    for &(day, _) in &days {
+        // 前後を祝日に挟まれた平日は国民の休日 (1986 年から)
+        let next = day + chrono::TimeDelta::days(2);
+        let between = day + chrono::TimeDelta::days(1);
+        if since(1986)
+            && is_holiday(next, &days)
+            && !is_holiday(between, &days)
+            && between.weekday() != Weekday::Sun
+        {
+            extra.push((between, "国民の休日"));
+        }
+        // 日曜日の祝日の後の最初の平日は振替休日 (1973 年から、2006 年までは翌日だけ)
+        if day.weekday() == Weekday::Sun && day >= date(1973, 4, 12).unwrap() {
+            let mut sub = day + chrono::TimeDelta::days(1);
+            while since(2007) && is_holiday(sub, &days) {
+                sub += chrono::TimeDelta::days(1);
+            }
+            if !is_holiday(sub, &days) {
+                extra.push((sub, "振替休日"));
+            }
+        }
+    }
+    days.extend(extra);
+    days.sort();
+    days
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn names(holidays: &Holidays, y: i32, m: u32, d: u32) -> Vec<String> {
+        holidays.on(date(y, m, d).unwrap())
+    }
+
+    #[test]
+    fn japanese_holidays() {
+        let holidays = Holidays {
+            countries: vec![Country::Jp],
+            special: vec![],
+        };
+        assert_eq!(holidays.in_year(2025).len(), 19);
+        assert_eq!(names(&holidays, 2025, 1, 13), ["成人の日"]);
+        assert_eq!(names(&holidays, 2025, 3, 20), ["春分の日"]);
+        assert_eq!(names(&holidays, 2025, 9, 23), ["秋分の日"]);
+        // 2025-02-24 と 2025-05-06 と 2025-11-24 は振替休日
+        assert_eq!(names(&holidays, 2025, 2, 24), ["振替休日"]);
+        assert_eq!(names(&holidays, 2025, 5, 6), ["振替休日"]);
+        assert_eq!(names(&holidays, 2025, 11, 24), ["振替休日"]);
+        // 2026-09-22 は敬老の日と秋分の日に挟まれた国民の休日
+        assert_eq!(names(&holidays, 2026, 9, 22), ["国民の休日"]);
+        assert!(names(&holidays, 2025, 9, 22).is_empty());
+    }
+
+    #[test]
+    fn past_japanese_holidays() {
+        let holidays = Holidays {
+            countries: vec![Country::Jp],
+            special: vec![],
+        };
+        // 山の日は 2016 年から
+        assert!(names(&holidays, 2015, 8, 11).is_empty());
+        assert_eq!(names(&holidays, 2016, 8, 11), ["山の日"]);
+        // 天皇誕生日は 2018 年まで 12/23、2019 年はなく、2020 年から 2/23
+        assert_eq!(names(&holidays, 2018, 12, 23), ["天皇誕生日"]);
+        assert!(
+            !holidays
+                .in_year(2019)
+                .iter()
+                .any(|(_, n)| n == "天皇誕生日")
+        );
+        assert_eq!(names(&holidays, 2020, 2, 23), ["天皇誕生日"]);
+        assert_eq!(names(&holidays, 2019, 5, 1), ["天皇の即位の日"]);
+        assert_eq!(names(&holidays, 1988, 4, 29), ["天皇誕生日"]);
+        assert_eq!(names(&holidays, 1999, 1, 15), ["成人の日"]);
+        // 東京オリンピックで移った祝日
+        assert_eq!(names(&holidays, 2020, 7, 24), ["スポーツの日"]);
+        assert!(names(&holidays, 2020, 10, 12).is_empty());
+        assert_eq!(names(&holidays, 2021, 8, 8), ["山の日"]);
+        assert_eq!(names(&holidays, 2021, 8, 9), ["振替休日"]);
+        assert!(holidays.in_year(1948).is_empty());
+    }
+
+    #[test]
+    fn european_holidays() {
+        assert_eq!(easter(2024), date(2024, 3, 31));
+        assert_eq!(easter(2025), date(2025, 4, 20));
+        assert_eq!(easter(2038), date(2038, 4, 25));
+        let holidays = Holidays {
+            countries: vec![Country::Gb, Country::De],
+            special: vec![],
+        };
+        assert_eq!(names(&holidays, 2025, 5, 26), ["Spring bank holiday"]);
+        assert_eq!(names(&holidays, 2025, 6, 9), ["Pfingstmontag"]);
+        assert_eq!(names(&holidays, 2025, 4, 18), ["Good Friday", "Karfreitag"]);
+        // 2021 年はクリスマスとボクシング・デーが両方週末
+        assert_eq!(names(&holidays, 2021, 12, 27), ["Christmas Day"]);
+        assert_eq!(names(&holidays, 2021, 12, 28), ["Boxing Day"]);
+    }
+
+    #[test]
+    fn far_years() {
+        let holidays = Holidays {
+            countries: vec![Country::Jp, Country::Gb, Country::De, Country::Fr],
+            special: vec![],
+        };
+        // 春分と秋分の式は 1980 年から 2099 年まで
+        assert!(!holidays.in_year(1979).iter().any(|(_, n)| n == "春分の日"));
+        assert_eq!(names(&holidays, 2099, 9, 23), ["秋分の日"]);
+        for year in [-262143, -1, 0, 1000, 1083, 4524, 9999, 262142] {
+            assert!(!holidays.in_year(year).is_empty(), "{year}");
+        }
+    }
+
+    #[test]
+    fn reads_holidays_file() {
+        let special = parse_holidays(
+            "# team days\n2025-12-24 Office closed\n02-29 Leap day  # every leap year\n",
+        )
+        .unwrap();
+        let holidays = Holidays {
+            countries: vec![],
+            special,
+        };
+        assert_eq!(names(&holidays, 2025, 12, 24), ["Office closed"]);
+        assert!(names(&holidays, 2026, 12, 24).is_empty());
+        assert_eq!(names(&holidays, 2028, 2, 29), ["Leap day"]);
+        assert_eq!(holidays.in_year(2027).len(), 0);
+        assert_eq!(
+            parse_holidays("2025-02-30 Nope"),
+            Err(ParseHolidaysError::Invalid(
+                1,
+                "2025-02-30 Nope".to_string()
+            ))
+        );
+        assert_eq!(
+            parse_holidays("\nsoon Party"),
+            Err(ParseHolidaysError::Invalid(2, "soon Party".to_string()))
+        );
+    }
+}
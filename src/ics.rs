@@ -0,0 +1,277 @@
+use chrono::{NaiveDate, NaiveDateTime, TimeZone};
+
+/// An event read from an iCalendar (`.ics`) file, in local time.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Event {
+    pub summary: String,
+    pub start: NaiveDateTime,
+    /// Exclusive end; the same as `start` if the file gives none.
+    pub end: NaiveDateTime,
+    pub all_day: bool,
+}
+impl Event {
+    /// Days the event falls on.
+    pub fn days(&self) -> impl Iterator<Item = NaiveDate> {
+        let last = if self.end > self.start {
+            (self.end - chrono::TimeDelta::seconds(1)).date()
+        } else {
+            self.start.date()
+        };
+        self.start
+            .date()
+            .iter_days()
+            .take_while(move |d| *d <= last)
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum ParseIcsError {
+    /// A `BEGIN:VEVENT` without its `END:VEVENT`.
+    Unterminated,
+    /// A date or time that could not be read, with its line number.
+    DateTime(usize, String),
+    /// An event without `DTSTART`, with the line number of its `BEGIN:VEVENT`.
+    NoStart(usize),
+}
+impl std::fmt::Display for ParseIcsError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ParseIcsError::Unterminated => write!(f, "unterminated event"),
+            ParseIcsError::DateTime(line, s) => write!(f, "line {line}: invalid date: {s}"),
+            ParseIcsError::NoStart(line) => write!(f, "line {line}: event without a start"),
+        }
+    }
+}
+impl std::error::Error for ParseIcsError {}
+
+/// Joins folded lines, which continue on the next line after a space or a tab (RFC 5545 3.1).
+///
+/// Returns each logical line with the number of the line it starts on.
+fn unfold(text: &str) -> Vec<(usize, String)> {
+    let mut lines: Vec<(usize, String)> = vec![];
+    for (i, line) in text.lines().enumerate() {
+        match (line.strip_prefix([' ', '\t']), lines.last_mut()) {
+            (Some(rest), Some((_, last))) => last.push_str(rest),
+            _ => lines.push((i + 1, line.to_string())),
+        }
+    }
+    lines
+}
+
+/// Undoes the escapes of TEXT values.
+fn unescape(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    let mut chars = s.chars().peekable();
+    while let Some(c) = chars.next() {
+        match (c, chars.next_if(|_| c == '\\')) {
+            (_, Some('n' | 'N')) => out.push('\n'),
+            (_, Some(escaped)) => out.push(escaped),
+            (c, None) => out.push(c),
+        }
+    }
+    out
+}
+
+/// Reads a `DTSTART` or `DTEND` value.
+///
+/// Dates are all-day; times ending in `Z` are UTC, times with a known `TZID` are in that zone,
+/// and any other time is taken as local time.
+fn parse_date_time(params: &str, value: &str) -> Option<(NaiveDateTime, bool)> {
+    if let Ok(date) = NaiveDate::parse_from_str(value, "%Y%m%d") {
+        return Some((date.and_hms_opt(0, 0, 0)?, true));
+    }
+    let (value, utc) = match value.strip_suffix('Z') {
+        Some(v) => (v, true),
+        None => (value, false),
+    };
+    let t = NaiveDateTime::parse_from_str(value, "%Y%m%dT%H%M%S").ok()?;
+    let tz = params
+        .split(';')
+        .find_map(|p| p.strip_prefix("TZID="))
+        .and_then(|name| name.trim_matches('"').parse::<chrono_tz::Tz>().ok());
+    let local = if utc {
+        chrono::Utc
+            .from_utc_datetime(&t)
+            .with_timezone(&chrono::Local)
+    } else if let Some(tz) = tz {
+        tz.from_local_datetime(&t)
+            .earliest()?
+            .with_timezone(&chrono::Local)
+    } else {
+        return Some((t, false));
+    };
+    Some((local.naive_local(), false))
+}
+
+/// Reads the events of an iCalendar file.
+///
+/// Only `SUMMARY`, `DTSTART` and `DTEND` are used; recurrence rules are ignored, so a recurring
+/// event only shows its first occurrence. Components inside an event, such as `VALARM`, are
+/// skipped.
+pub fn parse_ics(text: &str) -> Result<Vec<Event>, ParseIcsError> {
+    let mut events = vec![];
+    let mut current: Option<Draft> = None;
+    for (line_no, line) in unfold(text) {
+        let Some((name, value)) = line.split_once(':') else {
+            continue;
+        };
+        let (name, params) = name.split_once(';').unwrap_or((name, ""));
+        let date_time = || {
+            parse_date_time(params, value)
+                .ok_or_else(|| ParseIcsError::DateTime(line_no, value.to_string()))
+        };
+        match (name.to_ascii_uppercase().as_str(), &mut current) {
+            ("BEGIN", None) if value.eq_ignore_ascii_case("VEVENT") => {
+                current = Some(Draft {
+                    begin: line_no,
+                    depth: 0,
+                    summary: String::new(),
+                    start: None,
+                    end: None,
+                });
+            }
+            ("BEGIN", Some(draft)) => draft.depth += 1,
+            ("END", Some(draft)) if draft.depth > 0 => draft.depth -= 1,
+            // VALARM などの中の SUMMARY はイベントのものではない
+            (_, Some(draft)) if draft.depth > 0 => {}
+            ("END", Some(draft)) if value.eq_ignore_ascii_case("VEVENT") => {
+                let (start, all_day) = draft.start.ok_or(ParseIcsError::NoStart(draft.begin))?;
+                let end = draft.end.unwrap_or(if all_day {
+                    start + chrono::TimeDelta::days(1)
+                } else {
+                    start
+                });
+                events.push(Event {
+                    summary: std::mem::take(&mut draft.summary),
+                    start,
+                    end,
+                    all_day,
+                });
+                current = None;
+            }
+            ("SUMMARY", Some(draft)) => draft.summary = unescape(value),
+            ("DTSTART", Some(draft)) => draft.start = Some(date_time()?),
+            ("DTEND", Some(draft)) => draft.end = Some(date_time()?.0),
+            _ => {}
+        }
+    }
+    match current {
+        Some(_) => Err(ParseIcsError::Unterminated),
+        None => Ok(events),
+    }
+}
+
+/// An event whose `END:VEVENT` has not been read yet.
+struct Draft {
+    /// Line number of its `BEGIN:VEVENT`.
+    begin: usize,
+    /// Number of components such as `VALARM` open inside the event.
+    depth: usize,
+    summary: String,
+    start: Option<(NaiveDateTime, bool)>,
+    end: Option<NaiveDateTime>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(y: i32, m: u32, d: u32, h: u32, mi: u32) -> NaiveDateTime {
+        NaiveDate::from_ymd_opt(y, m, d)
+            .unwrap()
+            .and_hms_opt(h, mi, 0)
+            .unwrap()
+    }
+
+    #[test]
+    fn reads_events() {
+        let text = "BEGIN:VCALENDAR\r\n\
+            VERSION:2.0\r\n\
+            BEGIN:VEVENT\r\n\
+            SUMMARY:Team sync\\, weekly\r\n\
+            DTSTART:20250101T100000\r\n\
+            DTEND:20250101T103000\r\n\
+            END:VEVENT\r\n\
+            BEGIN:VEVENT\r\n\
+            SUMMARY:Conference in\r\n  Tokyo\r\n\
+            DTSTART;VALUE=DATE:20250110\r\n\
+            DTEND;VALUE=DATE:20250112\r\n\
+            END:VEVENT\r\n\
+            END:VCALENDAR\r\n";
+        let events = parse_ics(text).unwrap();
+        assert_eq!(
+            events,
+            vec![
+                Event {
+                    summary: "Team sync, weekly".to_string(),
+                    start: at(2025, 1, 1, 10, 0),
+                    end: at(2025, 1, 1, 10, 30),
+                    all_day: false,
+                },
+                Event {
+                    summary: "Conference in Tokyo".to_string(),
+                    start: at(2025, 1, 10, 0, 0),
+                    end: at(2025, 1, 12, 0, 0),
+                    all_day: true,
+                },
+            ]
+        );
+        let days: Vec<_> = events[1].days().map(|d| d.to_string()).collect();
+        assert_eq!(days, ["2025-01-10", "2025-01-11"]);
+        assert_eq!(events[0].days().count(), 1);
+    }
+
+    #[test]
+    fn skips_alarms_inside_events() {
+        let text = "BEGIN:VEVENT\n\
+            DTSTART:20250101T100000\n\
+            BEGIN:VALARM\n\
+            ACTION:EMAIL\n\
+            SUMMARY:Alarm notification\n\
+            TRIGGER:-P0DT0H10M0S\n\
+            END:VALARM\n\
+            SUMMARY:Review\n\
+            BEGIN:VALARM\n\
+            SUMMARY:Another reminder\n\
+            DTSTART:19700101T000000\n\
+            END:VALARM\n\
+            END:VEVENT\n";
+        let events = parse_ics(text).unwrap();
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].summary, "Review");
+        assert_eq!(events[0].start, at(2025, 1, 1, 10, 0));
+    }
+
+    #[test]
+    fn converts_zones_to_local_time() {
+        let text = "BEGIN:VEVENT\n\
+            DTSTART;TZID=Asia/Tokyo:20250101T090000\n\
+            DTEND:20250101T010000Z\n\
+            END:VEVENT\n";
+        let event = &parse_ics(text).unwrap()[0];
+        let expected = chrono::Utc
+            .with_ymd_and_hms(2025, 1, 1, 0, 0, 0)
+            .unwrap()
+            .with_timezone(&chrono::Local)
+            .naive_local();
+        assert_eq!(event.start, expected);
+        assert_eq!(event.end, expected + chrono::TimeDelta::hours(1));
+    }
+
+    #[test]
+    fn reports_errors() {
+        assert_eq!(
+            parse_ics("BEGIN:VEVENT\nDTSTART:tomorrow\nEND:VEVENT"),
+            Err(ParseIcsError::DateTime(2, "tomorrow".to_string()))
+        );
+        assert_eq!(
+            parse_ics("BEGIN:VEVENT\nSUMMARY:x\nEND:VEVENT"),
+            Err(ParseIcsError::NoStart(1))
+        );
+        assert_eq!(
+            parse_ics("BEGIN:VEVENT\nDTSTART:20250101\n"),
+            Err(ParseIcsError::Unterminated)
+        );
+        assert_eq!(unescape(r"a\;b\\c\nd"), "a;b\\c\nd");
+    }
+}
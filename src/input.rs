@@ -0,0 +1,91 @@
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use ratatui::{
+    Frame,
+    layout::{Position, Rect},
+    style::{Modifier, Stylize},
+    text::{Line, Span},
+};
+
+/// A single-line text input shown in a tab's panel.
+///
+/// While one is open the tab receives every key, including the ones that would otherwise quit
+/// the app or switch tabs.
+#[derive(Debug, Default)]
+pub struct InputLine {
+    prompt: String,
+    buffer: String,
+}
+
+/// What happened to an [`InputLine`] after a key press.
+#[derive(Debug, PartialEq, Eq)]
+pub enum InputEvent {
+    /// Still editing.
+    Pending,
+    /// [Enter] was pressed; holds the entered text.
+    Submit(String),
+    /// [Esc] was pressed.
+    Cancel,
+}
+
+impl InputLine {
+    pub fn new(prompt: &str) -> InputLine {
+        InputLine {
+            prompt: prompt.to_string(),
+            buffer: String::new(),
+        }
+    }
+
+    pub fn handle_key_event(&mut self, key: &KeyEvent) -> InputEvent {
+        match key.code {
+            KeyCode::Enter => InputEvent::Submit(std::mem::take(&mut self.buffer)),
+            KeyCode::Esc => InputEvent::Cancel,
+            KeyCode::Backspace => {
+                self.buffer.pop();
+                InputEvent::Pending
+            }
+            // Ctrl や Alt との組み合わせは文字として入力しない
+            KeyCode::Char(c) if (key.modifiers - KeyModifiers::SHIFT).is_empty() => {
+                self.buffer.push(c);
+                InputEvent::Pending
+            }
+            _ => InputEvent::Pending,
+        }
+    }
+
+    /// Renders the prompt and the text, and places the terminal cursor after the text.
+    pub fn render(&self, frame: &mut Frame, area: Rect) {
+        let line = Line::from(vec![
+            Span::from(format!("{} ", self.prompt)).add_modifier(Modifier::DIM),
+            Span::from(&self.buffer[..]).add_modifier(Modifier::BOLD),
+        ]);
+        let width = line.width() as u16;
+        let x = area.x + area.width.saturating_sub(width) / 2 + width;
+        frame.render_widget(line.centered(), area);
+        if !area.is_empty() && x < area.right() {
+            frame.set_cursor_position(Position::new(x, area.y));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ignores_modified_chars() {
+        let mut input = InputLine::new(">");
+        for (c, modifiers) in [
+            ('a', KeyModifiers::NONE),
+            ('B', KeyModifiers::SHIFT),
+            ('c', KeyModifiers::CONTROL),
+            ('d', KeyModifiers::ALT),
+            ('E', KeyModifiers::CONTROL | KeyModifiers::SHIFT),
+        ] {
+            input.handle_key_event(&KeyEvent::new(KeyCode::Char(c), modifiers));
+        }
+        assert_eq!(
+            input.handle_key_event(&KeyEvent::from(KeyCode::Enter)),
+            InputEvent::Submit("aB".to_string())
+        );
+    }
+}
@@ -0,0 +1,240 @@
+use ratatui::layout::{Constraint, Direction, Layout, Rect};
+
+/// Minimum width of the bordered digital readout.
+const DIGITS_WIDTH: u16 = 18;
+/// Height of the bordered digital readout.
+const DIGITS_HEIGHT: u16 = 3;
+
+/// Size classes of the main layout, chosen from the frame area.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SizeClass {
+    /// Too small for a dial; only the digital readout is shown.
+    Tiny,
+    /// The dial, with the panel stacked below it if there is enough height.
+    Narrow,
+    /// The panel on the left and the dial on the right.
+    Wide,
+    /// Like [`SizeClass::Wide`], with an extra column summarizing the other modes.
+    Dashboard,
+}
+
+impl SizeClass {
+    /// Picks the size class for the area below the tab bar.
+    fn of(body: Rect) -> SizeClass {
+        if body.width >= 120 && body.height >= 20 {
+            SizeClass::Dashboard
+        } else if body.width >= 50 && body.height >= 10 {
+            SizeClass::Wide
+        } else if body.width >= 20 && body.height >= 9 {
+            SizeClass::Narrow
+        } else {
+            SizeClass::Tiny
+        }
+    }
+}
+
+/// Areas of the main screen. Areas that are not shown in the current size class are empty.
+#[derive(Debug, Clone, Copy)]
+pub struct AppLayout {
+    pub size_class: SizeClass,
+    pub tabs: Rect,
+    pub panel: Rect,
+    pub dial: Rect,
+    pub digits: Rect,
+    pub summary: Rect,
+}
+
+/// Width of the bordered digital readout showing `lines`, with room for the border.
+pub fn digits_width<'a>(lines: impl IntoIterator<Item = &'a str>) -> u16 {
+    lines
+        .into_iter()
+        .map(|s| {
+            let width = ratatui::text::Line::from(s).width();
+            u16::try_from(width).unwrap_or(u16::MAX).saturating_add(4)
+        })
+        .fold(DIGITS_WIDTH, std::cmp::max)
+}
+
+/// Splits the frame area into the areas of [`AppLayout`], with a digital readout `digits_width`
+/// wide.
+///
+/// All arithmetic saturates, so any area, including an empty one, gives a valid layout.
+pub fn compute(area: Rect, digits_width: u16) -> AppLayout {
+    // 高さが足りない場合はタブを表示しない
+    let (tabs, body) = if area.height > DIGITS_HEIGHT {
+        let v = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints(vec![Constraint::Length(1), Constraint::Min(0)])
+            .split(area);
+        (v[0], v[1])
+    } else {
+        (Rect::default(), area)
+    };
+    let size_class = SizeClass::of(body);
+    let h = body.height;
+    let w = body.width;
+    let dial_h = h.saturating_sub(DIGITS_HEIGHT);
+    let mut layout = AppLayout {
+        size_class,
+        tabs,
+        panel: Rect::default(),
+        dial: Rect::default(),
+        digits: Rect::default(),
+        summary: Rect::default(),
+    };
+    match size_class {
+        SizeClass::Tiny => {
+            let v = Layout::default()
+                .direction(Direction::Vertical)
+                .constraints(vec![
+                    Constraint::Min(0),
+                    Constraint::Length(std::cmp::min(DIGITS_HEIGHT, h)),
+                    Constraint::Min(0),
+                ])
+                .split(body);
+            layout.digits = center_digits(v[1], digits_width);
+        }
+        SizeClass::Narrow => {
+            // パネルは十分な高さがある場合のみダイヤルの下に表示する
+            let panel_h = if h >= 24 { h / 2 } else { 0 };
+            let v = Layout::default()
+                .direction(Direction::Vertical)
+                .constraints(vec![Constraint::Min(0), Constraint::Length(panel_h)])
+                .split(body);
+            layout.panel = v[1];
+            let top = v[0];
+            let dial_col = Layout::default()
+                .direction(Direction::Horizontal)
+                .constraints(vec![
+                    Constraint::Min(0),
+                    Constraint::Length(std::cmp::min(
+                        top.height.saturating_sub(DIGITS_HEIGHT).saturating_mul(2),
+                        w,
+                    )),
+                    Constraint::Min(0),
+                ])
+                .split(top)[1];
+            (layout.dial, layout.digits) = split_dial(digits_width, dial_col);
+        }
+        SizeClass::Wide => {
+            // 中央の正方形のエリアを取り出す
+            let h_layout = Layout::default()
+                .direction(Direction::Horizontal)
+                .constraints(vec![
+                    Constraint::Min(0),
+                    Constraint::Length(std::cmp::min(h.saturating_mul(2), w / 2)),
+                    Constraint::Min(0),
+                    Constraint::Length(std::cmp::min(dial_h.saturating_mul(2), w / 2)),
+                    Constraint::Min(0),
+                ])
+                .split(body);
+            layout.panel = h_layout[1];
+            (layout.dial, layout.digits) = split_dial(digits_width, h_layout[3]);
+        }
+        SizeClass::Dashboard => {
+            let h_layout = Layout::default()
+                .direction(Direction::Horizontal)
+                .constraints(vec![
+                    Constraint::Min(0),
+                    Constraint::Length(std::cmp::min(h.saturating_mul(2), w / 3)),
+                    Constraint::Min(0),
+                    Constraint::Length(std::cmp::min(dial_h.saturating_mul(2), w / 3)),
+                    Constraint::Min(0),
+                    Constraint::Length(std::cmp::min(32, w / 4)),
+                    Constraint::Min(0),
+                ])
+                .split(body);
+            layout.panel = h_layout[1];
+            (layout.dial, layout.digits) = split_dial(digits_width, h_layout[3]);
+            layout.summary = h_layout[5];
+        }
+    }
+    layout
+}
+
+/// Splits a column into a square dial area and the digital readout below it.
+fn split_dial(digits_width: u16, area: Rect) -> (Rect, Rect) {
+    let v = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints(vec![
+            Constraint::Min(0),
+            Constraint::Length(
+                std::cmp::min(
+                    area.height.saturating_sub(DIGITS_HEIGHT).saturating_mul(2),
+                    area.width,
+                ) / 2,
+            ),
+            Constraint::Min(0),
+            Constraint::Length(DIGITS_HEIGHT),
+        ])
+        .split(area);
+    (v[1], center_digits(v[3], digits_width))
+}
+
+fn center_digits(area: Rect, width: u16) -> Rect {
+    Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints(vec![
+            Constraint::Min(0),
+            Constraint::Length(width),
+            Constraint::Min(0),
+        ])
+        .split(area)[1]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn size_classes() {
+        assert_eq!(
+            compute(Rect::new(0, 0, 10, 3), DIGITS_WIDTH).size_class,
+            SizeClass::Tiny
+        );
+        assert_eq!(
+            compute(Rect::new(0, 0, 40, 20), DIGITS_WIDTH).size_class,
+            SizeClass::Narrow
+        );
+        assert_eq!(
+            compute(Rect::new(0, 0, 80, 24), DIGITS_WIDTH).size_class,
+            SizeClass::Wide
+        );
+        assert_eq!(
+            compute(Rect::new(0, 0, 160, 40), DIGITS_WIDTH).size_class,
+            SizeClass::Dashboard
+        );
+    }
+
+    #[test]
+    fn digits_fit_their_text() {
+        assert_eq!(
+            digits_width(["12:00:00 PM", "2025-01-01 Wed"]),
+            DIGITS_WIDTH
+        );
+        let width = digits_width(["2025-01-01T12:00:00+09:00", "Wednesday"]);
+        assert_eq!(width, 29);
+        assert_eq!(compute(Rect::new(0, 0, 80, 24), width).digits.width, 29);
+        // 極端に長い書式でも桁あふれしない
+        assert_eq!(digits_width(["x".repeat(70_000).as_str()]), u16::MAX);
+    }
+
+    #[test]
+    fn areas_stay_inside_frame() {
+        for width in (0..25).chain((25..200).step_by(25)) {
+            for height in (0..14).chain((14..80).step_by(11)) {
+                let area = Rect::new(0, 0, width, height);
+                let layout = compute(area, 40);
+                for r in [
+                    layout.tabs,
+                    layout.panel,
+                    layout.dial,
+                    layout.digits,
+                    layout.summary,
+                ] {
+                    assert_eq!(area.union(r), area, "{width}x{height}: {layout:?}");
+                }
+            }
+        }
+    }
+}
@@ -1,74 +1,216 @@
-use std::{f64::consts::PI, time::Duration};
+use std::time::Duration;
 
-use chrono::Timelike;
 use crossterm::event::{self, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
 use ratatui::{
     DefaultTerminal, Frame,
-    layout::{Constraint, Direction, Layout},
-    style::{Color, Modifier, Style, Stylize},
+    layout::{Constraint, Direction, Layout, Rect},
+    style::{Color, Modifier, Stylize},
     text,
-    widgets::{Block, BorderType, Padding, Paragraph, Tabs, calendar, canvas::Canvas},
+    widgets::{Block, BorderType, Paragraph, Tabs, canvas::Canvas},
 };
-use time::OffsetDateTime;
 
 fn main() -> color_eyre::Result<()> {
     color_eyre::install()?;
+    let config = config::load_config()?;
     let terminal = ratatui::init();
-    let result = App::new().run(terminal);
+    let result = App::new(config).run(terminal);
     ratatui::restore();
     result
 }
 
+mod alarm_tab;
+mod alert;
 mod analog;
 mod clock_tab;
+mod config;
+mod holidays;
+mod ics;
+mod input;
+mod layout;
+mod monotonic;
+mod month_view;
+mod pomodoro_tab;
+mod sequence_tab;
 mod stopwatch_tab;
+mod sun;
+mod time_source;
 mod timer_tab;
+mod world_tab;
+
+use time_source::TimeSource;
 
 /// The main application which holds the state and logic of the application.
+///
+/// `T` is where the current time comes from; tests substitute a fake one.
 #[derive(Debug, Default)]
-pub struct App {
+pub struct App<T: TimeSource = monotonic::MonotonicClock> {
     /// Is the application running?
     running: bool,
     selected_tab: AppTab,
+    clock: T,
+    config: config::Config,
     sw: stopwatch_tab::StopWatchState,
     timer: timer_tab::TimerState,
+    pomodoro: pomodoro_tab::PomodoroState,
+    sequence: sequence_tab::SequenceState,
+    alarm: alarm_tab::AlarmState,
+    world: world_tab::WorldState,
+    calendar: clock_tab::CalendarState,
 }
 
-#[derive(Default, Debug, Clone, Copy)]
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq)]
 enum AppTab {
     #[default]
     Clock,
     Timer,
     StopWatch,
+    Pomodoro,
+    Sequence,
+    Alarm,
+    World,
+}
+impl AppTab {
+    /// Every tab, in the order shown in the tab bar.
+    const ALL: [AppTab; 7] = [
+        AppTab::Clock,
+        AppTab::Timer,
+        AppTab::StopWatch,
+        AppTab::Pomodoro,
+        AppTab::Sequence,
+        AppTab::Alarm,
+        AppTab::World,
+    ];
+
+    fn name(self) -> &'static str {
+        match self {
+            AppTab::Clock => "Clock",
+            AppTab::Timer => "Timer",
+            AppTab::StopWatch => "StopWatch",
+            AppTab::Pomodoro => "Pomodoro",
+            AppTab::Sequence => "Sequence",
+            AppTab::Alarm => "Alarm",
+            AppTab::World => "World",
+        }
+    }
+
+    fn next(self) -> AppTab {
+        AppTab::ALL[(self as usize + 1) % AppTab::ALL.len()]
+    }
+
+    fn prev(self) -> AppTab {
+        AppTab::ALL[(self as usize + AppTab::ALL.len() - 1) % AppTab::ALL.len()]
+    }
 }
 
 pub struct DisplayData {
     block_title: String,
     block_content: String,
+    content_color: Color,
     analog_state: analog::ClockState,
 }
 
 impl App {
     /// Construct a new instance of [`App`].
-    pub fn new() -> Self {
-        Self::default()
+    pub fn new(config: config::Config) -> Self {
+        let mut app = Self {
+            config,
+            ..Self::default()
+        };
+        if let Some(dir) = config::dir() {
+            timer_tab::load_presets(&mut app.timer, dir.join("presets.toml"));
+            sequence_tab::load_sequences(&mut app.sequence, dir.join("sequences"));
+            alarm_tab::load_alarms(&mut app.alarm, dir.join("alarms.toml"));
+            clock_tab::load_holidays(
+                &mut app.calendar,
+                &app.config.calendar,
+                dir.join("holidays.txt"),
+            );
+        }
+        pomodoro_tab::reset(&mut app.pomodoro, &app.config.pomodoro);
+        world_tab::load_zones(&mut app.world, &app.config.world);
+        clock_tab::load_events(&mut app.calendar, &app.config.calendar);
+        app
     }
+}
 
+impl<T: TimeSource> App<T> {
     /// Run the application's main loop.
     pub fn run(mut self, mut terminal: DefaultTerminal) -> color_eyre::Result<()> {
         self.running = true;
         while self.running {
-            let display = match self.selected_tab {
-                AppTab::Clock => clock_tab::update_chrono(),
-                AppTab::Timer => timer_tab::update_timer(&mut self.timer),
-                AppTab::StopWatch => stopwatch_tab::update_sw(&self.sw),
-            };
+            if self.tick() {
+                print!("\x07");
+            }
+            let display = self.display();
             terminal.draw(|frame| self.render(frame, display))?;
             self.handle_crossterm_events()?;
         }
         Ok(())
     }
 
+    /// Advances the state of every mode, regardless of which tab is currently shown.
+    ///
+    /// Expiry and alerts must not depend on the selected tab, so anything that changes over time
+    /// belongs here rather than in the `update_*` functions used for rendering.
+    ///
+    /// Returns whether the terminal bell should ring.
+    fn tick(&mut self) -> bool {
+        self.clock.tick();
+        let now = self.clock.instant();
+        let timer = timer_tab::tick_timer(&mut self.timer, now, &self.config);
+        let pomodoro = pomodoro_tab::tick_pomodoro(&mut self.pomodoro, now, &self.config);
+        let sequence = sequence_tab::tick_sequence(&mut self.sequence, now, &self.config);
+        let alarm = alarm_tab::tick_alarms(&mut self.alarm, now, self.clock.local(), &self.config);
+        timer || pomodoro || sequence || alarm
+    }
+
+    /// Whether an alert wants the whole screen inverted now.
+    fn is_flashing(&self) -> bool {
+        let now = self.clock.instant();
+        timer_tab::is_flashing(&self.timer, now, &self.config)
+            || pomodoro_tab::is_flashing(&self.pomodoro, now, &self.config)
+            || sequence_tab::is_flashing(&self.sequence, now, &self.config)
+            || alarm_tab::is_flashing(&self.alarm, now, &self.config)
+    }
+
+    /// Computes what the dial and the digital readout show for the selected tab.
+    fn display(&self) -> DisplayData {
+        self.display_for(self.selected_tab)
+    }
+
+    fn display_for(&self, tab: AppTab) -> DisplayData {
+        match tab {
+            AppTab::Clock => world_tab::update_clock(
+                &self.world,
+                self.clock.local(),
+                &self.config.clock,
+                self.config.location.as_ref(),
+            ),
+            AppTab::Timer => {
+                timer_tab::update_timer(&self.timer, self.clock.instant(), &self.config)
+            }
+            AppTab::StopWatch => stopwatch_tab::update_sw(&self.sw, self.clock.instant()),
+            AppTab::Pomodoro => {
+                pomodoro_tab::update_pomodoro(&self.pomodoro, self.clock.instant(), &self.config)
+            }
+            AppTab::Sequence => {
+                sequence_tab::update_sequence(&self.sequence, self.clock.instant(), &self.config)
+            }
+            AppTab::Alarm => alarm_tab::update_alarm(
+                &self.alarm,
+                self.clock.instant(),
+                self.clock.local(),
+                &self.config,
+            ),
+            AppTab::World => world_tab::update_world(
+                &self.world,
+                self.clock.local(),
+                &self.config.clock,
+                self.config.location.as_ref(),
+            ),
+        }
+    }
+
     /// Renders the user interface.
     ///
     /// This is where you add new widgets. See the following resources for more information:
@@ -76,74 +218,34 @@ impl App {
     /// - <https://docs.rs/ratatui/latest/ratatui/widgets/index.html>
     /// - <https://github.com/ratatui/ratatui/tree/main/ratatui-widgets/examples>
     fn render(&mut self, frame: &mut Frame, display: DisplayData) {
-        let vertical_layout = Layout::default()
-            .direction(Direction::Vertical)
-            .constraints(vec![Constraint::Length(1), Constraint::Min(0)])
-            .split(frame.area());
-        let tabs_area = vertical_layout[0];
-        let canvas_area = vertical_layout[1];
-        // 中央の正方形のエリアを取り出す
-        let canvas_h_layout = Layout::default()
-            .direction(Direction::Horizontal)
-            .constraints(if canvas_area.width >= 50 && canvas_area.height >= 10 {
-                vec![
-                    Constraint::Min(0),
-                    Constraint::Length(std::cmp::min(
-                        canvas_area.height * 2,
-                        canvas_area.width / 2,
-                    )),
-                    Constraint::Min(0),
-                    Constraint::Length(std::cmp::min(
-                        (canvas_area.height - 3) * 2,
-                        canvas_area.width / 2,
-                    )),
-                    Constraint::Min(0),
-                ]
-            } else {
-                vec![
-                    Constraint::Min(0),
-                    Constraint::Length(0),
-                    Constraint::Length(0),
-                    Constraint::Length(std::cmp::min(
-                        (canvas_area.height - 3) * 2,
-                        canvas_area.width,
-                    )),
-                    Constraint::Min(0),
-                ]
-            })
-            .split(canvas_area);
-        let panel_area = canvas_h_layout[1];
-        let canvas_area = canvas_h_layout[3];
-        let canvas_v_layout = Layout::default()
-            .direction(Direction::Vertical)
-            .constraints(vec![
-                Constraint::Min(0),
-                Constraint::Length(
-                    std::cmp::min((canvas_area.height - 3) * 2, canvas_area.width) / 2,
-                ),
-                Constraint::Min(0),
-                Constraint::Length(3),
-            ])
-            .split(canvas_area);
-        let digit_area = canvas_v_layout[3];
-        let digit_area = Layout::default()
-            .direction(Direction::Horizontal)
-            .constraints(vec![
-                Constraint::Min(0),
-                Constraint::Length(18),
-                Constraint::Min(0),
-            ])
-            .split(digit_area)[1];
-        let canvas_area = canvas_v_layout[1];
+        let app_layout = layout::compute(
+            frame.area(),
+            layout::digits_width([&display.block_content[..], &display.block_title[..]]),
+        );
 
-        let tab_description: String = "Select Mode with [Tab]:".to_string();
+        let names = AppTab::ALL.map(AppTab::name);
+        let selected = self.selected_tab as usize;
+        // 幅が狭いときは説明を省略してタブだけを表示する
+        let tab_description = match app_layout.size_class {
+            layout::SizeClass::Wide | layout::SizeClass::Dashboard
+                if TAB_DESCRIPTION.len() as u16 + tabs_width(&names) <= app_layout.tabs.width =>
+            {
+                TAB_DESCRIPTION
+            }
+            _ => "",
+        };
+        // 入り切らない場合は選択中のタブが見えるように左側のタブを省く
+        let mut first = 0;
+        while first < selected && tabs_width(&names[first..]) > app_layout.tabs.width {
+            first += 1;
+        }
         let tabs_layout = Layout::default()
             .direction(Direction::Horizontal)
             .constraints(vec![
                 Constraint::Length(tab_description.len() as u16),
                 Constraint::Min(0),
             ])
-            .split(tabs_area);
+            .split(app_layout.tabs);
         let description_area = tabs_layout[0];
         let tabs_area = tabs_layout[1];
 
@@ -152,48 +254,106 @@ impl App {
             description_area,
         );
         frame.render_widget(
-            Tabs::new(vec!["Clock", "Timer", "StopWatch"])
+            Tabs::new(names[first..].to_vec())
                 .highlight_style(Modifier::BOLD | Modifier::ITALIC)
-                .select(self.selected_tab as usize),
+                .select(selected - first),
             tabs_area,
         );
-        match self.selected_tab {
-            AppTab::Clock => clock_tab::render_panel(frame, panel_area),
-            AppTab::Timer => timer_tab::render_panel(frame, panel_area, &self.timer),
-            AppTab::StopWatch => stopwatch_tab::render_panel(frame, panel_area, &self.sw),
-        };
-        frame.render_widget(
-            Canvas::default()
-                .x_bounds([-1., 1.])
-                .y_bounds([-1., 1.])
-                .paint(|ctx| analog::draw(ctx, &canvas_area, &display.analog_state)),
-            canvas_area,
-        );
-        frame.render_widget(
-            Paragraph::new(&display.block_content[..])
-                .add_modifier(Modifier::ITALIC)
-                .add_modifier(Modifier::BOLD)
-                .remove_modifier(Modifier::DIM)
-                .fg(Color::Reset)
-                .centered()
-                .block(
-                    Block::bordered()
-                        .border_type(BorderType::Rounded)
-                        .title(
-                            text::Line::from(&display.block_title[..])
-                                .centered()
-                                .add_modifier(Modifier::ITALIC),
-                            // ↓不要
-                            // .remove_modifier(Modifier::BOLD)
-                            // .add_modifier(Modifier::DIM)
-                        )
-                        .fg(Color::Reset)
-                        .remove_modifier(Modifier::ITALIC)
-                        .remove_modifier(Modifier::BOLD)
-                        .add_modifier(Modifier::DIM),
+        let panel_area = app_layout.panel;
+        if !panel_area.is_empty() {
+            match self.selected_tab {
+                AppTab::Clock => clock_tab::render_panel(
+                    frame,
+                    panel_area,
+                    &self.calendar,
+                    &self.config,
+                    world_tab::clock_today(&self.world, self.clock.local()),
+                    self.clock.local().naive_local(),
+                    alarm_tab::next_alarm_text(&self.alarm, self.clock.local()),
                 ),
-            digit_area,
-        );
+                AppTab::Timer => timer_tab::render_panel(
+                    frame,
+                    panel_area,
+                    &self.timer,
+                    self.clock.instant(),
+                    self.clock.local(),
+                    &self.config,
+                ),
+                AppTab::StopWatch => stopwatch_tab::render_panel(
+                    frame,
+                    panel_area,
+                    &self.sw,
+                    self.clock.instant(),
+                    self.clock.local(),
+                ),
+                AppTab::Pomodoro => pomodoro_tab::render_panel(
+                    frame,
+                    panel_area,
+                    &self.pomodoro,
+                    self.clock.instant(),
+                    self.clock.local(),
+                    &self.config,
+                ),
+                AppTab::Sequence => sequence_tab::render_panel(
+                    frame,
+                    panel_area,
+                    &self.sequence,
+                    self.clock.instant(),
+                    &self.config,
+                ),
+                AppTab::Alarm => alarm_tab::render_panel(
+                    frame,
+                    panel_area,
+                    &self.alarm,
+                    self.clock.instant(),
+                    self.clock.local(),
+                    &self.config,
+                ),
+                AppTab::World => {
+                    world_tab::render_panel(frame, panel_area, &self.world, self.clock.local())
+                }
+            };
+        }
+        let canvas_area = app_layout.dial;
+        if !canvas_area.is_empty() {
+            frame.render_widget(
+                Canvas::default()
+                    .x_bounds([-1., 1.])
+                    .y_bounds([-1., 1.])
+                    .paint(|ctx| analog::draw(ctx, &canvas_area, &display.analog_state)),
+                canvas_area,
+            );
+        }
+        render_digits(frame, app_layout.digits, &display);
+        if !app_layout.summary.is_empty() {
+            self.render_summary(frame, app_layout.summary);
+        }
+        if self.is_flashing() {
+            for cell in frame.buffer_mut().content.iter_mut() {
+                cell.modifier.toggle(Modifier::REVERSED);
+            }
+        }
+    }
+
+    /// Renders the digital readouts of the tabs that are not selected, one below another.
+    fn render_summary(&self, frame: &mut Frame, summary_area: Rect) {
+        let tabs: Vec<_> = AppTab::ALL
+            .into_iter()
+            .filter(|&tab| tab != self.selected_tab)
+            .collect();
+        let summary_layout = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints(
+                std::iter::once(Constraint::Min(0))
+                    .chain(tabs.iter().map(|_| Constraint::Length(3)))
+                    .chain(std::iter::once(Constraint::Min(0))),
+            )
+            .split(summary_area);
+        for (i, &tab) in tabs.iter().enumerate() {
+            let mut display = self.display_for(tab);
+            display.block_title = format!("{}: {}", tab.name(), display.block_title);
+            render_digits(frame, summary_layout[i + 1], &display);
+        }
     }
 
     /// Reads the crossterm events and updates the state of [`App`].
@@ -215,30 +375,70 @@ impl App {
 
     /// Handles the key events and updates the state of [`App`].
     fn on_key_event(&mut self, key: KeyEvent) {
+        // テキスト入力中は Ctrl-C 以外のキーをすべてタブに渡す
+        let typing = match self.selected_tab {
+            AppTab::Clock | AppTab::Pomodoro | AppTab::Sequence | AppTab::World => false,
+            AppTab::Timer => timer_tab::is_typing(&self.timer),
+            AppTab::StopWatch => stopwatch_tab::is_typing(&self.sw),
+            AppTab::Alarm => alarm_tab::is_typing(&self.alarm),
+        };
+        if typing && !(key.modifiers == KeyModifiers::CONTROL && key.code == KeyCode::Char('c')) {
+            match self.selected_tab {
+                AppTab::Clock | AppTab::Pomodoro | AppTab::Sequence | AppTab::World => {}
+                AppTab::Timer => timer_tab::handle_key_event(
+                    &mut self.timer,
+                    &key,
+                    self.clock.instant(),
+                    &self.config,
+                ),
+                AppTab::StopWatch => {
+                    stopwatch_tab::handle_key_event(&mut self.sw, &key, self.clock.instant())
+                }
+                AppTab::Alarm => alarm_tab::handle_key_event(
+                    &mut self.alarm,
+                    &key,
+                    self.clock.local(),
+                    &self.config,
+                ),
+            }
+            return;
+        }
         match (key.modifiers, key.code) {
             (_, KeyCode::Esc | KeyCode::Char('q'))
             | (KeyModifiers::CONTROL, KeyCode::Char('c') | KeyCode::Char('C')) => self.quit(),
             // Add other key handlers here.
-            (KeyModifiers::SHIFT, KeyCode::Tab) => {
-                self.selected_tab = match self.selected_tab {
-                    AppTab::Clock => AppTab::StopWatch,
-                    AppTab::Timer => AppTab::Clock,
-                    AppTab::StopWatch => AppTab::Timer,
-                }
-            }
-            (_, KeyCode::Tab) => {
-                self.selected_tab = match self.selected_tab {
-                    AppTab::Clock => AppTab::Timer,
-                    AppTab::Timer => AppTab::StopWatch,
-                    AppTab::StopWatch => AppTab::Clock,
-                }
+            (KeyModifiers::SHIFT, KeyCode::Tab) | (_, KeyCode::BackTab) => {
+                self.selected_tab = self.selected_tab.prev()
             }
+            (_, KeyCode::Tab) => self.selected_tab = self.selected_tab.next(),
             _ => {}
         }
         match self.selected_tab {
-            AppTab::Clock => (),
-            AppTab::Timer => timer_tab::handle_key_event(&mut self.timer, &key),
-            AppTab::StopWatch => stopwatch_tab::handle_key_event(&mut self.sw, &key),
+            AppTab::Clock => {
+                clock_tab::handle_key_event(&mut self.config.clock, &mut self.calendar, &key)
+            }
+            AppTab::Timer => timer_tab::handle_key_event(
+                &mut self.timer,
+                &key,
+                self.clock.instant(),
+                &self.config,
+            ),
+            AppTab::StopWatch => {
+                stopwatch_tab::handle_key_event(&mut self.sw, &key, self.clock.instant())
+            }
+            AppTab::Pomodoro => pomodoro_tab::handle_key_event(
+                &mut self.pomodoro,
+                &key,
+                self.clock.instant(),
+                &self.config,
+            ),
+            AppTab::Sequence => {
+                sequence_tab::handle_key_event(&mut self.sequence, &key, self.clock.instant())
+            }
+            AppTab::Alarm => {
+                alarm_tab::handle_key_event(&mut self.alarm, &key, self.clock.local(), &self.config)
+            }
+            AppTab::World => world_tab::handle_key_event(&mut self.world, &key),
         };
     }
 
@@ -247,3 +447,372 @@ impl App {
         self.running = false;
     }
 }
+
+const TAB_DESCRIPTION: &str = "Select Mode with [Tab]:";
+
+/// Width of a tab bar showing `names`, with the padding and the dividers of [`Tabs`].
+fn tabs_width(names: &[&str]) -> u16 {
+    let width: usize = names.iter().map(|name| name.len() + 3).sum();
+    width.saturating_sub(1) as u16
+}
+
+/// Renders the digital readout, with its title on the border if there is room for one.
+fn render_digits(frame: &mut Frame, digit_area: Rect, display: &DisplayData) {
+    let paragraph = Paragraph::new(&display.block_content[..])
+        .add_modifier(Modifier::ITALIC)
+        .add_modifier(Modifier::BOLD)
+        .remove_modifier(Modifier::DIM)
+        .fg(display.content_color)
+        .centered();
+    if digit_area.height < 3 {
+        frame.render_widget(paragraph, digit_area);
+        return;
+    }
+    frame.render_widget(
+        paragraph.block(
+            Block::bordered()
+                .border_type(BorderType::Rounded)
+                .title(
+                    text::Line::from(&display.block_title[..])
+                        .centered()
+                        .add_modifier(Modifier::ITALIC),
+                    // ↓不要
+                    // .remove_modifier(Modifier::BOLD)
+                    // .add_modifier(Modifier::DIM)
+                )
+                .fg(Color::Reset)
+                .remove_modifier(Modifier::ITALIC)
+                .remove_modifier(Modifier::BOLD)
+                .add_modifier(Modifier::DIM),
+        ),
+        digit_area,
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::time_source::FakeTimeSource;
+    use ratatui::{Terminal, backend::TestBackend};
+
+    fn press(app: &mut App<FakeTimeSource>, code: KeyCode) {
+        app.on_key_event(KeyEvent::new(code, KeyModifiers::NONE));
+    }
+
+    /// Ticks and renders `app` into a fresh [`TestBackend`] of the given size.
+    fn render(app: &mut App<FakeTimeSource>, width: u16, height: u16) -> TestBackend {
+        let mut terminal = Terminal::new(TestBackend::new(width, height)).unwrap();
+        app.tick();
+        let display = app.display();
+        terminal.draw(|frame| app.render(frame, display)).unwrap();
+        terminal.backend().clone()
+    }
+
+    /// A timer of 3:25 that has been running for 1:10.
+    fn running_timer() -> App<FakeTimeSource> {
+        let mut app = App::<FakeTimeSource> {
+            selected_tab: AppTab::Timer,
+            ..Default::default()
+        };
+        for _ in 0..3 {
+            press(&mut app, KeyCode::Up);
+        }
+        press(&mut app, KeyCode::Right);
+        for _ in 0..2 {
+            press(&mut app, KeyCode::Up);
+        }
+        press(&mut app, KeyCode::Right);
+        for _ in 0..5 {
+            press(&mut app, KeyCode::Up);
+        }
+        press(&mut app, KeyCode::Char(' '));
+        app.clock.advance(Duration::from_secs(70));
+        app
+    }
+
+    /// A stopwatch with two laps, running for 1:23.456 in total.
+    fn running_stopwatch() -> App<FakeTimeSource> {
+        let mut app = App::<FakeTimeSource> {
+            selected_tab: AppTab::StopWatch,
+            ..Default::default()
+        };
+        press(&mut app, KeyCode::Char(' '));
+        app.clock.advance(Duration::from_millis(20_100));
+        press(&mut app, KeyCode::Char('m'));
+        app.clock.advance(Duration::from_millis(31_200));
+        press(&mut app, KeyCode::Char('m'));
+        app.clock.advance(Duration::from_millis(32_156));
+        app
+    }
+
+    #[test]
+    fn timer_expires_on_other_tab() {
+        let mut app = App::<FakeTimeSource>::default();
+        press(&mut app, KeyCode::Tab);
+        press(&mut app, KeyCode::Up);
+        press(&mut app, KeyCode::Char(' '));
+        press(&mut app, KeyCode::Tab);
+        assert!(matches!(app.selected_tab, AppTab::StopWatch));
+
+        app.clock.advance(Duration::from_secs(61));
+        app.tick();
+        app.selected_tab = AppTab::Timer;
+        assert_eq!(app.display().block_content, "+0:00:01.000");
+    }
+
+    #[test]
+    fn flash_inverts_screen() {
+        let mut app = App::<FakeTimeSource> {
+            selected_tab: AppTab::Timer,
+            config: config::Config {
+                alert: alert::AlertConfig {
+                    flash: true,
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        press(&mut app, KeyCode::Char('1'));
+        press(&mut app, KeyCode::Char(' '));
+        app.clock.advance(Duration::from_secs(2));
+        let backend = render(&mut app, 80, 24);
+        assert!(
+            backend.buffer()[(0, 0)]
+                .modifier
+                .contains(Modifier::REVERSED)
+        );
+        app.clock.advance(Duration::from_secs(1));
+        let backend = render(&mut app, 80, 24);
+        assert!(
+            !backend.buffer()[(0, 0)]
+                .modifier
+                .contains(Modifier::REVERSED)
+        );
+    }
+
+    #[test]
+    fn typing_captures_quit_keys() {
+        let mut app = App::<FakeTimeSource> {
+            running: true,
+            selected_tab: AppTab::Timer,
+            ..Default::default()
+        };
+        press(&mut app, KeyCode::Enter);
+        press(&mut app, KeyCode::Char('q'));
+        press(&mut app, KeyCode::Tab);
+        assert!(app.running);
+        assert!(matches!(app.selected_tab, AppTab::Timer));
+        press(&mut app, KeyCode::Esc);
+        assert!(app.running);
+        press(&mut app, KeyCode::Char('q'));
+        assert!(!app.running);
+    }
+
+    #[test]
+    fn snapshot_clock() {
+        let mut app = App::<FakeTimeSource>::default();
+        insta::assert_snapshot!("clock_80x24", render(&mut app, 80, 24));
+        insta::assert_snapshot!("clock_120x40", render(&mut app, 120, 40));
+        insta::assert_snapshot!("clock_40x20", render(&mut app, 40, 20));
+        press(&mut app, KeyCode::Char('h'));
+        insta::assert_snapshot!("clock_24h_80x24", render(&mut app, 80, 24));
+        app.config.clock.date_format = Some("week".to_string());
+        app.config.clock.time_format = Some("%A %H:%M:%S".to_string());
+        insta::assert_snapshot!("clock_format_80x24", render(&mut app, 80, 24));
+    }
+
+    #[test]
+    fn snapshot_calendar_events() {
+        let path =
+            std::env::temp_dir().join(format!("atclock-snapshot-{}.ics", std::process::id()));
+        std::fs::write(
+            &path,
+            "BEGIN:VCALENDAR\n\
+            BEGIN:VEVENT\nSUMMARY:Standup\nDTSTART:20250101T090000\nDTEND:20250101T091500\nEND:VEVENT\n\
+            BEGIN:VEVENT\nSUMMARY:Design review\nDTSTART:20250101T140000\nEND:VEVENT\n\
+            BEGIN:VEVENT\nSUMMARY:New Year's Day\nDTSTART;VALUE=DATE:20250101\nEND:VEVENT\n\
+            BEGIN:VEVENT\nSUMMARY:Trip\nDTSTART;VALUE=DATE:20250116\nDTEND;VALUE=DATE:20250119\nEND:VEVENT\n\
+            END:VCALENDAR\n",
+        )
+        .unwrap();
+        let mut app = App::<FakeTimeSource>::default();
+        app.config.calendar.ics = vec![path.clone()];
+        clock_tab::load_events(&mut app.calendar, &app.config.calendar);
+        std::fs::remove_file(&path).unwrap();
+        insta::assert_snapshot!("calendar_events_80x24", render(&mut app, 80, 24));
+    }
+
+    #[test]
+    fn snapshot_holidays() {
+        let mut app = App::<FakeTimeSource>::default();
+        app.config.calendar.holidays = vec!["jp".to_string(), "xx".to_string()];
+        clock_tab::load_holidays(
+            &mut app.calendar,
+            &app.config.calendar,
+            std::env::temp_dir().join("atclock-no-holidays.txt"),
+        );
+        insta::assert_snapshot!("clock_holidays_80x24", render(&mut app, 80, 24));
+    }
+
+    #[test]
+    fn pages_to_far_years() {
+        let mut app = App::<FakeTimeSource>::default();
+        app.config.calendar.holidays = ["jp", "gb", "de", "fr"].map(String::from).to_vec();
+        clock_tab::load_holidays(
+            &mut app.calendar,
+            &app.config.calendar,
+            std::env::temp_dir().join("atclock-no-holidays.txt"),
+        );
+        // 1025 年と 5025 年でも描画できる
+        for _ in 0..1000 {
+            press(&mut app, KeyCode::Up);
+        }
+        assert!(format!("{:?}", render(&mut app, 80, 24)).contains("January 1025"));
+        for _ in 0..4000 {
+            press(&mut app, KeyCode::Down);
+        }
+        assert!(format!("{:?}", render(&mut app, 80, 24)).contains("January 5025"));
+    }
+
+    #[test]
+    fn snapshot_calendar() {
+        let mut app = App::<FakeTimeSource>::default();
+        press(&mut app, KeyCode::Right);
+        press(&mut app, KeyCode::Char('v'));
+        insta::assert_snapshot!("calendar_months_160x40", render(&mut app, 160, 40));
+        press(&mut app, KeyCode::Char('v'));
+        insta::assert_snapshot!("calendar_year_240x60", render(&mut app, 240, 60));
+    }
+
+    #[test]
+    fn snapshot_size_classes() {
+        let mut app = running_timer();
+        insta::assert_snapshot!("timer_tiny_20x3", render(&mut app, 20, 3));
+        insta::assert_snapshot!("timer_stacked_40x40", render(&mut app, 40, 40));
+        insta::assert_snapshot!("timer_dashboard_160x40", render(&mut app, 160, 40));
+    }
+
+    #[test]
+    fn tiny_terminals_do_not_panic() {
+        for tab in AppTab::ALL {
+            let mut app = running_stopwatch();
+            app.selected_tab = tab;
+            for width in 0..30 {
+                for height in 0..12 {
+                    render(&mut app, width, height);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn snapshot_timer() {
+        let mut app = running_timer();
+        insta::assert_snapshot!("timer_80x24", render(&mut app, 80, 24));
+        insta::assert_snapshot!("timer_40x20", render(&mut app, 40, 20));
+    }
+
+    #[test]
+    fn snapshot_timer_presets() {
+        let mut app = App::<FakeTimeSource> {
+            selected_tab: AppTab::Timer,
+            ..Default::default()
+        };
+        for (digits, name) in [("300", "Tea"), ("2500", "Pomodoro"), ("13000", "")] {
+            for c in digits.chars().chain(['s']).chain(name.chars()) {
+                press(&mut app, KeyCode::Char(c));
+            }
+            press(&mut app, KeyCode::Enter);
+        }
+        press(&mut app, KeyCode::Char('p'));
+        press(&mut app, KeyCode::Down);
+        insta::assert_snapshot!("timer_presets_80x24", render(&mut app, 80, 24));
+    }
+
+    #[test]
+    fn snapshot_pomodoro() {
+        let mut app = App::<FakeTimeSource> {
+            selected_tab: AppTab::Pomodoro,
+            ..Default::default()
+        };
+        press(&mut app, KeyCode::Char(' '));
+        app.clock.advance(Duration::from_secs(25 * 60 + 1));
+        app.tick();
+        app.clock.advance(Duration::from_secs(90));
+        insta::assert_snapshot!("pomodoro_80x24", render(&mut app, 80, 24));
+    }
+
+    #[test]
+    fn snapshot_sequence() {
+        let dir = std::env::temp_dir().join(format!("atclock-snapshot-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("hiit.txt"), "Warmup 5m, (Work 40s, Rest 20s) x8").unwrap();
+        std::fs::write(dir.join("standup.txt"), "Updates 10m\nBlockers 5m").unwrap();
+        let mut app = App::<FakeTimeSource> {
+            selected_tab: AppTab::Sequence,
+            ..Default::default()
+        };
+        sequence_tab::load_sequences(&mut app.sequence, dir.clone());
+        std::fs::remove_dir_all(&dir).unwrap();
+        press(&mut app, KeyCode::Enter);
+        app.clock.advance(Duration::from_secs(5 * 60 + 1));
+        app.tick();
+        app.clock.advance(Duration::from_secs(15));
+        insta::assert_snapshot!("sequence_80x24", render(&mut app, 80, 24));
+    }
+
+    #[test]
+    fn snapshot_alarm() {
+        let mut app = App::<FakeTimeSource> {
+            selected_tab: AppTab::Alarm,
+            ..Default::default()
+        };
+        for text in ["12:30 Lunch weekdays", "7:00 Wake up mon,wed,fri", "18:00"] {
+            press(&mut app, KeyCode::Char('a'));
+            for c in text.chars() {
+                press(&mut app, KeyCode::Char(c));
+            }
+            press(&mut app, KeyCode::Enter);
+        }
+        insta::assert_snapshot!("alarm_80x24", render(&mut app, 80, 24));
+        app.selected_tab = AppTab::Clock;
+        insta::assert_snapshot!("clock_next_alarm_40x40", render(&mut app, 40, 40));
+        app.clock.advance(Duration::from_secs(30 * 60));
+        app.selected_tab = AppTab::Alarm;
+        insta::assert_snapshot!("alarm_ringing_80x24", render(&mut app, 80, 24));
+    }
+
+    #[test]
+    fn snapshot_world() {
+        let mut app = App::<FakeTimeSource> {
+            selected_tab: AppTab::World,
+            clock: FakeTimeSource::at_utc(2025, 1, 1, 12, 0, 0),
+            ..Default::default()
+        };
+        world_tab::load_zones(&mut app.world, &app.config.world);
+        press(&mut app, KeyCode::Down);
+        press(&mut app, KeyCode::Down);
+        press(&mut app, KeyCode::Down);
+        insta::assert_snapshot!("world_80x24", render(&mut app, 80, 24));
+        press(&mut app, KeyCode::Enter);
+        app.selected_tab = AppTab::Clock;
+        insta::assert_snapshot!("clock_world_80x24", render(&mut app, 80, 24));
+    }
+
+    #[test]
+    fn snapshot_stopwatch() {
+        let mut app = running_stopwatch();
+        insta::assert_snapshot!("stopwatch_80x24", render(&mut app, 80, 24));
+        insta::assert_snapshot!("stopwatch_40x20", render(&mut app, 40, 20));
+
+        press(&mut app, KeyCode::Char('n'));
+        for c in "Relay 2".chars() {
+            press(&mut app, KeyCode::Char(c));
+        }
+        press(&mut app, KeyCode::Enter);
+        press(&mut app, KeyCode::Char(' '));
+        app.clock.advance(Duration::from_millis(4_321));
+        insta::assert_snapshot!("stopwatches_80x24", render(&mut app, 80, 24));
+    }
+}
@@ -0,0 +1,152 @@
+use std::time::{Duration, Instant};
+
+/// Monotonic time source for the timer and the stopwatch.
+///
+/// [`Instant`] is immune to NTP steps, manual clock changes and DST, but on Linux and macOS it
+/// does not advance while the system is suspended, so a running timer would silently lose the
+/// time spent asleep. To handle this, every tick compares [`Instant`] against a clock that keeps
+/// counting during suspend (see [`continuous`]) and adds the difference to the returned time.
+/// The wall clock is never read, so changing it does not move timers.
+#[derive(Debug)]
+pub struct MonotonicClock {
+    start_instant: Instant,
+    /// Reading of [`continuous`] at `start_instant`, if the platform has such a clock.
+    start_continuous: Option<Duration>,
+    suspended: Duration,
+}
+impl Default for MonotonicClock {
+    fn default() -> MonotonicClock {
+        MonotonicClock::new(Instant::now(), continuous())
+    }
+}
+
+impl MonotonicClock {
+    fn new(instant: Instant, continuous: Option<Duration>) -> MonotonicClock {
+        MonotonicClock {
+            start_instant: instant,
+            start_continuous: continuous,
+            suspended: Duration::ZERO,
+        }
+    }
+
+    /// Detects a suspend since the previous tick. Call this once per main loop iteration.
+    pub fn tick(&mut self) {
+        if let Some(c) = continuous() {
+            self.observe(Instant::now(), c);
+        }
+    }
+
+    fn observe(&mut self, instant: Instant, continuous: Duration) {
+        let Some(start) = self.start_continuous else {
+            return;
+        };
+        let mono_elapsed = instant.saturating_duration_since(self.start_instant);
+        let suspended = continuous
+            .saturating_sub(start)
+            .saturating_sub(mono_elapsed);
+        // 読み取りのずれで戻らないように増える方向にだけ更新する
+        self.suspended = self.suspended.max(suspended);
+    }
+
+    /// Current time, including the time the system spent suspended.
+    pub fn now(&self) -> Instant {
+        Instant::now() + self.suspended
+    }
+}
+
+/// Clock that keeps counting while the system is suspended.
+#[cfg(any(target_os = "linux", target_os = "android"))]
+const CONTINUOUS_CLOCK: libc::clockid_t = libc::CLOCK_BOOTTIME;
+/// Clock that keeps counting while the system is suspended; unlike Linux, Apple's
+/// `CLOCK_MONOTONIC` does (it is `mach_continuous_time`).
+#[cfg(any(target_os = "macos", target_os = "ios"))]
+const CONTINUOUS_CLOCK: libc::clockid_t = libc::CLOCK_MONOTONIC;
+
+/// Time since an arbitrary point, including the time the system spent suspended.
+#[cfg(any(
+    target_os = "linux",
+    target_os = "android",
+    target_os = "macos",
+    target_os = "ios"
+))]
+fn continuous() -> Option<Duration> {
+    let mut ts = libc::timespec {
+        tv_sec: 0,
+        tv_nsec: 0,
+    };
+    // SAFETY: `ts` is a valid, writable timespec for the duration of the call.
+    if unsafe { libc::clock_gettime(CONTINUOUS_CLOCK, &mut ts) } != 0 {
+        return None;
+    }
+    Some(Duration::new(ts.tv_sec as u64, ts.tv_nsec as u32))
+}
+/// Elsewhere, e.g. on Windows, [`Instant`] already counts suspend or no such clock is known.
+#[cfg(not(any(
+    target_os = "linux",
+    target_os = "android",
+    target_os = "macos",
+    target_os = "ios"
+)))]
+fn continuous() -> Option<Duration> {
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn suspend_is_added() {
+        let instant = Instant::now();
+        let boot = Duration::from_secs(1000);
+        let mut clock = MonotonicClock::new(instant, Some(boot));
+        // Instant は 10ms しか進んでいないが、スリープを含む時計は 30 分進んだ
+        clock.observe(
+            instant + Duration::from_millis(10),
+            boot + Duration::from_secs(30 * 60) + Duration::from_millis(10),
+        );
+        assert_eq!(clock.suspended, Duration::from_secs(30 * 60));
+        // 次の通常の tick で二重に足されない
+        clock.observe(
+            instant + Duration::from_millis(20),
+            boot + Duration::from_secs(30 * 60) + Duration::from_millis(20),
+        );
+        assert_eq!(clock.suspended, Duration::from_secs(30 * 60));
+    }
+
+    #[test]
+    fn suspended_time_never_goes_down() {
+        let instant = Instant::now();
+        let boot = Duration::from_secs(1000);
+        let mut clock = MonotonicClock::new(instant, Some(boot));
+        // スリープを含む時計が読み取りの誤差で Instant より遅れても負にならない
+        clock.observe(
+            instant + Duration::from_millis(10),
+            boot + Duration::from_millis(9),
+        );
+        assert_eq!(clock.suspended, Duration::ZERO);
+
+        clock.observe(
+            instant + Duration::from_millis(20),
+            boot + Duration::from_secs(60) + Duration::from_millis(20),
+        );
+        assert_eq!(clock.suspended, Duration::from_secs(60));
+        // 遅れて読まれても一度足した時間は減らない
+        clock.observe(
+            instant + Duration::from_millis(30),
+            boot + Duration::from_secs(59),
+        );
+        assert_eq!(clock.suspended, Duration::from_secs(60));
+    }
+
+    #[test]
+    fn instant_alone_without_a_continuous_clock() {
+        let instant = Instant::now();
+        let mut clock = MonotonicClock::new(instant, None);
+        clock.observe(
+            instant + Duration::from_millis(10),
+            Duration::from_secs(3600),
+        );
+        assert_eq!(clock.suspended, Duration::ZERO);
+    }
+}
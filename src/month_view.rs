@@ -0,0 +1,149 @@
+use std::collections::HashMap;
+
+use chrono::{Datelike, NaiveDate, Weekday};
+use ratatui::{
+    buffer::Buffer,
+    layout::Rect,
+    style::{Color, Modifier, Style, Stylize},
+    text::{Line, Span},
+    widgets::{Block, BorderType, Widget},
+};
+use serde::Deserialize;
+
+/// Color of Saturdays and Sundays.
+const WEEKEND_COLOR: Color = Color::Cyan;
+
+/// Day the weeks of the calendar start on.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum WeekStart {
+    #[default]
+    Sunday,
+    Monday,
+}
+impl WeekStart {
+    fn weekday(self) -> Weekday {
+        match self {
+            WeekStart::Sunday => Weekday::Sun,
+            WeekStart::Monday => Weekday::Mon,
+        }
+    }
+}
+
+/// Width of a month, with its border.
+pub fn width(week_numbers: bool) -> u16 {
+    if week_numbers { 27 } else { 24 }
+}
+
+/// Height of a month, with its border and room for six weeks.
+pub const HEIGHT: u16 = 10;
+
+/// A bordered month, like `ratatui::widgets::calendar::Monthly` with a configurable first day
+/// of the week and ISO week numbers.
+pub struct MonthView<'a> {
+    /// Any day of the month to show.
+    pub month: NaiveDate,
+    /// Styles of days, such as today or holidays, applied over the default style.
+    pub styles: &'a HashMap<NaiveDate, Style>,
+    pub week_start: WeekStart,
+    pub week_numbers: bool,
+}
+
+fn is_weekend(day: NaiveDate) -> bool {
+    matches!(day.weekday(), Weekday::Sat | Weekday::Sun)
+}
+
+impl Widget for MonthView<'_> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let block = Block::bordered()
+            .border_type(BorderType::Rounded)
+            .add_modifier(Modifier::DIM);
+        let inner = block.inner(area);
+        block.render(area, buf);
+        let first = self.month.with_day(1).unwrap();
+        let week_start = self.week_start.weekday();
+
+        let mut lines = vec![
+            Line::from(first.format("%B %Y").to_string())
+                .centered()
+                .remove_modifier(Modifier::DIM)
+                .add_modifier(Modifier::BOLD),
+        ];
+        let mut header = Line::default()
+            .add_modifier(Modifier::DIM)
+            .add_modifier(Modifier::ITALIC);
+        if self.week_numbers {
+            header.push_span(" Wk");
+        }
+        for weekday in std::iter::successors(Some(week_start), |d| Some(d.succ())).take(7) {
+            let name = format!(" {:.2}", weekday.to_string());
+            header.push_span(match weekday {
+                Weekday::Sat | Weekday::Sun => name.fg(WEEKEND_COLOR),
+                _ => name.into(),
+            });
+        }
+        lines.push(header);
+
+        let back = first.weekday().days_since(week_start);
+        let mut day = first - chrono::TimeDelta::days(back as i64);
+        while day.month() == first.month() || day < first {
+            let mut line = Line::default();
+            if self.week_numbers {
+                // ISO 週番号は月曜日で決まる
+                let monday =
+                    day + chrono::TimeDelta::days(Weekday::Mon.days_since(week_start) as i64);
+                line.push_span(
+                    format!("{:>3}", monday.iso_week().week()).add_modifier(Modifier::DIM),
+                );
+            }
+            for _ in 0..7 {
+                let text = format!("{:>3}", day.day());
+                let span = if day.month() != first.month() {
+                    text.add_modifier(Modifier::DIM)
+                } else {
+                    let mut style = Style::default().remove_modifier(Modifier::DIM);
+                    if is_weekend(day) {
+                        style = style.fg(WEEKEND_COLOR);
+                    }
+                    if let Some(&extra) = self.styles.get(&day) {
+                        style = style.patch(extra);
+                    }
+                    Span::styled(text, style)
+                };
+                line.push_span(span);
+                day += chrono::TimeDelta::days(1);
+            }
+            lines.push(line);
+        }
+
+        for (line, row) in lines.into_iter().zip(inner.rows()) {
+            line.render(row, buf);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn render(week_start: WeekStart, week_numbers: bool) -> Buffer {
+        let area = Rect::new(0, 0, width(week_numbers), HEIGHT);
+        let mut buf = Buffer::empty(area);
+        let today = NaiveDate::from_ymd_opt(2024, 12, 31).unwrap();
+        let styles = HashMap::from([(today, Style::default().add_modifier(Modifier::REVERSED))]);
+        MonthView {
+            month: today,
+            styles: &styles,
+            week_start,
+            week_numbers,
+        }
+        .render(area, &mut buf);
+        buf
+    }
+
+    #[test]
+    fn snapshot_month() {
+        insta::assert_debug_snapshot!("month_sunday", render(WeekStart::Sunday, false));
+        insta::assert_debug_snapshot!("month_monday_weeks", render(WeekStart::Monday, true));
+    }
+}
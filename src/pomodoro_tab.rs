@@ -0,0 +1,412 @@
+use std::time::Instant;
+
+use crossterm::event::{KeyCode, KeyEvent};
+use ratatui::{
+    Frame,
+    layout::{Constraint, Direction, Layout, Rect},
+    style::{Color, Modifier, Stylize},
+    text::{Line, Span, Text},
+};
+use serde::Deserialize;
+
+use super::alert::Alert;
+use super::config::Config;
+use super::timer_tab::{self, Timer};
+
+/// Work and break cycles, driven by a [`Timer`] that is reloaded at each phase change.
+#[derive(Debug)]
+pub struct PomodoroState {
+    timer: Timer,
+    phase: Phase,
+    /// Number of work phases finished since the last reset.
+    completed: u32,
+    /// Rings when a phase ends by itself.
+    alert: Option<Alert>,
+}
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Phase {
+    Work,
+    ShortBreak,
+    LongBreak,
+}
+impl Phase {
+    fn name(self) -> &'static str {
+        match self {
+            Phase::Work => "Work",
+            Phase::ShortBreak => "Short break",
+            Phase::LongBreak => "Long break",
+        }
+    }
+
+    fn color(self) -> Color {
+        match self {
+            Phase::Work => Color::LightRed,
+            Phase::ShortBreak => Color::LightGreen,
+            Phase::LongBreak => Color::LightBlue,
+        }
+    }
+}
+
+/// Settings of the `[pomodoro]` table of the config.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct PomodoroConfig {
+    pub work_minutes: u32,
+    pub short_break_minutes: u32,
+    pub long_break_minutes: u32,
+    /// A long break follows every this many work phases; 0 never takes one.
+    pub long_break_every: u32,
+    /// Start the next phase right away instead of waiting for [Space].
+    pub auto_start: bool,
+    /// Minutes added by [e].
+    pub extend_minutes: u32,
+}
+impl Default for PomodoroConfig {
+    fn default() -> PomodoroConfig {
+        PomodoroConfig {
+            work_minutes: 25,
+            short_break_minutes: 5,
+            long_break_minutes: 15,
+            long_break_every: 4,
+            auto_start: true,
+            extend_minutes: 5,
+        }
+    }
+}
+
+impl Default for PomodoroState {
+    fn default() -> PomodoroState {
+        let mut state = PomodoroState {
+            timer: Timer::new("Pomodoro".to_string()),
+            phase: Phase::Work,
+            completed: 0,
+            alert: None,
+        };
+        reset(&mut state, &PomodoroConfig::default());
+        state
+    }
+}
+
+/// Checks that no phase is zero minutes long, which would end as soon as it starts.
+pub fn check_config(config: &PomodoroConfig) -> color_eyre::Result<()> {
+    for (name, minutes) in [
+        ("work_minutes", config.work_minutes),
+        ("short_break_minutes", config.short_break_minutes),
+        ("long_break_minutes", config.long_break_minutes),
+    ] {
+        // 長い休憩を取らない設定ではその長さは使われない
+        if name == "long_break_minutes" && config.long_break_every == 0 {
+            continue;
+        }
+        if minutes == 0 {
+            color_eyre::eyre::bail!("{name} must be at least 1");
+        }
+    }
+    Ok(())
+}
+
+fn phase_duration(phase: Phase, config: &PomodoroConfig) -> chrono::TimeDelta {
+    let minutes = match phase {
+        Phase::Work => config.work_minutes,
+        Phase::ShortBreak => config.short_break_minutes,
+        Phase::LongBreak => config.long_break_minutes,
+    };
+    chrono::TimeDelta::minutes(minutes as i64)
+}
+
+/// Goes back to the first work phase, with the lengths from `config`.
+pub fn reset(state: &mut PomodoroState, config: &PomodoroConfig) {
+    state.phase = Phase::Work;
+    state.completed = 0;
+    state.alert = None;
+    timer_tab::load(&mut state.timer, phase_duration(Phase::Work, config));
+}
+
+/// Moves on to the phase after the current one.
+fn next_phase(state: &mut PomodoroState, now: Instant, config: &PomodoroConfig) {
+    state.phase = match state.phase {
+        Phase::Work => {
+            state.completed += 1;
+            if config.long_break_every != 0
+                && state.completed.is_multiple_of(config.long_break_every)
+            {
+                Phase::LongBreak
+            } else {
+                Phase::ShortBreak
+            }
+        }
+        Phase::ShortBreak | Phase::LongBreak => Phase::Work,
+    };
+    let duration = phase_duration(state.phase, config);
+    if config.auto_start {
+        timer_tab::start(&mut state.timer, duration, now);
+    } else {
+        timer_tab::load(&mut state.timer, duration);
+    }
+}
+
+/// Updates the timer and moves on to the next phase once it expires.
+///
+/// Returns whether the bell should ring now.
+pub fn tick_pomodoro(state: &mut PomodoroState, now: Instant, config: &Config) -> bool {
+    let mut ring = timer_tab::tick_one(&mut state.timer, now, config);
+    if let Some(alert) = timer_tab::take_alert(&mut state.timer) {
+        state.alert = Some(alert);
+        next_phase(state, now, &config.pomodoro);
+    }
+    if let Some(alert) = &mut state.alert {
+        ring |= alert.tick(now, &config.alert);
+    }
+    ring
+}
+
+/// Whether the phase change alert wants the screen inverted now.
+pub fn is_flashing(state: &PomodoroState, now: Instant, config: &Config) -> bool {
+    state
+        .alert
+        .as_ref()
+        .is_some_and(|a| a.is_flashing(now, &config.alert))
+}
+
+pub fn update_pomodoro(state: &PomodoroState, now: Instant, config: &Config) -> super::DisplayData {
+    // 枠の幅に収まるように、動いている間はフェーズ名だけを表示する
+    let block_title = if timer_tab::is_running(&state.timer) {
+        state.phase.name().to_string()
+    } else {
+        timer_tab::status_name(&state.timer, now, config).to_string()
+    };
+    let mut display = timer_tab::display_timer(&state.timer, block_title);
+    display.analog_state.ring = Some(super::analog::Ring {
+        fraction: timer_tab::progress(&state.timer),
+        color: state.phase.color(),
+    });
+    display
+}
+
+pub fn handle_key_event(state: &mut PomodoroState, key: &KeyEvent, now: Instant, config: &Config) {
+    match (key.modifiers, key.code) {
+        (_, KeyCode::Char(' ')) => {
+            state.alert = None;
+            timer_tab::toggle(&mut state.timer, now);
+        }
+        (_, KeyCode::Char('s')) => {
+            state.alert = None;
+            next_phase(state, now, &config.pomodoro);
+        }
+        (_, KeyCode::Char('e')) => timer_tab::extend(
+            &mut state.timer,
+            chrono::TimeDelta::minutes(config.pomodoro.extend_minutes as i64),
+        ),
+        (_, KeyCode::Char('d')) => {
+            if let Some(alert) = &mut state.alert {
+                alert.dismiss();
+            }
+        }
+        (_, KeyCode::Char('r')) => reset(state, &config.pomodoro),
+        _ => {}
+    }
+}
+
+pub fn render_panel(
+    frame: &mut Frame,
+    panel_area: Rect,
+    state: &PomodoroState,
+    now: Instant,
+    local_now: chrono::DateTime<chrono::Local>,
+    config: &Config,
+) {
+    let panel_layout = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints(vec![
+            Constraint::Min(0),
+            Constraint::Length(1),
+            Constraint::Length(1),
+            Constraint::Length(1),
+            Constraint::Length(1),
+            Constraint::Length(1),
+            Constraint::Length(1),
+            Constraint::Length(1),
+            Constraint::Min(0),
+        ])
+        .split(panel_area);
+
+    frame.render_widget(
+        Text::from("[Space]: Start / Pause  [r]: Reset")
+            .add_modifier(Modifier::DIM)
+            .centered(),
+        panel_layout[1],
+    );
+    frame.render_widget(
+        Text::from(format!(
+            "[s]: Skip  [e]: +{} min",
+            config.pomodoro.extend_minutes
+        ))
+        .add_modifier(Modifier::DIM)
+        .centered(),
+        panel_layout[2],
+    );
+    frame.render_widget(
+        Text::from(format!(
+            "Work {}  Break {}  Long break {}",
+            config.pomodoro.work_minutes,
+            config.pomodoro.short_break_minutes,
+            config.pomodoro.long_break_minutes
+        ))
+        .add_modifier(Modifier::DIM)
+        .centered(),
+        panel_layout[3],
+    );
+
+    // 長い休憩までの進み具合を丸で表示する
+    let every = config.pomodoro.long_break_every;
+    let mut spans = vec![Span::from(format!("Done: {}  ", state.completed))];
+    if every != 0 {
+        let in_set = match (state.completed % every, state.phase) {
+            (0, Phase::LongBreak) => every,
+            (n, _) => n,
+        };
+        spans.push(Span::from("●".repeat(in_set as usize)).fg(Phase::Work.color()));
+        spans.push(Span::from("○".repeat((every - in_set) as usize)).add_modifier(Modifier::DIM));
+    }
+    frame.render_widget(Line::from(spans).centered(), panel_layout[5]);
+
+    let active = state
+        .alert
+        .as_ref()
+        .is_some_and(|a| a.is_active(now, &config.alert));
+    if active {
+        let text = match state.phase {
+            Phase::Work => "Break is over  [d]: Dismiss",
+            Phase::ShortBreak | Phase::LongBreak => "Time for a break  [d]: Dismiss",
+        };
+        frame.render_widget(
+            Text::from(text).add_modifier(Modifier::ITALIC).centered(),
+            panel_layout[7],
+        );
+    } else if let Some(end) = timer_tab::ends_at(&state.timer, local_now) {
+        frame.render_widget(
+            Text::from(format!(
+                "{} ends at {}",
+                state.phase.name(),
+                end.format("%H:%M:%S")
+            ))
+            .add_modifier(Modifier::ITALIC)
+            .centered(),
+            panel_layout[7],
+        );
+    } else {
+        frame.render_widget(
+            Text::from(format!(
+                "{}: {}",
+                state.phase.name(),
+                timer_tab::status_name(&state.timer, now, config)
+            ))
+            .add_modifier(Modifier::ITALIC)
+            .centered(),
+            panel_layout[7],
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crossterm::event::KeyModifiers;
+    use std::time::Duration;
+
+    fn press(state: &mut PomodoroState, code: KeyCode, now: Instant, config: &Config) {
+        handle_key_event(state, &KeyEvent::new(code, KeyModifiers::NONE), now, config);
+    }
+
+    #[test]
+    fn cycles_through_phases() {
+        let start = Instant::now();
+        let at = |min: u64| start + Duration::from_secs(min * 60);
+        let config = Config {
+            pomodoro: PomodoroConfig {
+                long_break_every: 2,
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let mut state = PomodoroState::default();
+        press(&mut state, KeyCode::Char(' '), at(0), &config);
+        assert!(!tick_pomodoro(&mut state, at(10), &config));
+        assert_eq!(
+            update_pomodoro(&state, at(10), &config).block_content,
+            "0:15:00.000"
+        );
+
+        assert!(tick_pomodoro(&mut state, at(26), &config));
+        assert_eq!(state.phase, Phase::ShortBreak);
+        assert_eq!(state.completed, 1);
+        assert_eq!(
+            update_pomodoro(&state, at(26), &config).block_title,
+            "Short break"
+        );
+        press(&mut state, KeyCode::Char('d'), at(26), &config);
+        assert!(!tick_pomodoro(&mut state, at(27), &config));
+
+        tick_pomodoro(&mut state, at(32), &config);
+        assert_eq!(state.phase, Phase::Work);
+        tick_pomodoro(&mut state, at(58), &config);
+        assert_eq!(state.phase, Phase::LongBreak);
+        assert_eq!(state.completed, 2);
+
+        press(&mut state, KeyCode::Char('r'), at(60), &config);
+        assert_eq!(state.phase, Phase::Work);
+        assert_eq!(state.completed, 0);
+    }
+
+    #[test]
+    fn rejects_zero_length_phases() {
+        assert!(check_config(&PomodoroConfig::default()).is_ok());
+        let config = PomodoroConfig {
+            short_break_minutes: 0,
+            ..Default::default()
+        };
+        assert!(check_config(&config).is_err());
+        let config = PomodoroConfig {
+            long_break_every: 0,
+            long_break_minutes: 0,
+            ..Default::default()
+        };
+        assert!(check_config(&config).is_ok());
+        let config = PomodoroConfig {
+            long_break_minutes: 0,
+            ..Default::default()
+        };
+        assert!(check_config(&config).is_err());
+    }
+
+    #[test]
+    fn skip_and_extend() {
+        let start = Instant::now();
+        let at = |min: u64| start + Duration::from_secs(min * 60);
+        let config = Config {
+            pomodoro: PomodoroConfig {
+                auto_start: false,
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let mut state = PomodoroState::default();
+        press(&mut state, KeyCode::Char(' '), at(0), &config);
+        press(&mut state, KeyCode::Char('e'), at(1), &config);
+        tick_pomodoro(&mut state, at(10), &config);
+        let display = update_pomodoro(&state, at(10), &config);
+        assert_eq!(display.block_content, "0:20:00.000");
+        let ring = display.analog_state.ring.unwrap();
+        assert!((ring.fraction - 1. / 3.).abs() < 1e-9);
+        assert_eq!(ring.color, Color::LightRed);
+
+        // 自動で始めない設定では次のフェーズで待つ
+        press(&mut state, KeyCode::Char('s'), at(11), &config);
+        assert_eq!(state.phase, Phase::ShortBreak);
+        assert!(state.alert.is_none());
+        tick_pomodoro(&mut state, at(30), &config);
+        let display = update_pomodoro(&state, at(30), &config);
+        assert_eq!(display.block_title, "Idle");
+        assert_eq!(display.block_content, "0:05:00.000");
+    }
+}
@@ -0,0 +1,624 @@
+use std::{path::PathBuf, time::Instant};
+
+use crossterm::event::{KeyCode, KeyEvent};
+use ratatui::{
+    Frame,
+    layout::{Constraint, Direction, Layout, Rect},
+    style::{Color, Modifier, Stylize},
+    text::{Line, Text},
+};
+
+use super::alert::Alert;
+use super::config::Config;
+use super::timer_tab::{self, ParseDurationError, Timer};
+
+/// Segments run back to back, such as interval training or a meeting agenda.
+///
+/// Sequences are read from plain-text files; see [`parse_sequence`] for the format.
+#[derive(Debug)]
+pub struct SequenceState {
+    sequences: Vec<Sequence>,
+    /// Sequence shown in the list, and run by [Enter].
+    selected: usize,
+    /// Segment being run, if a sequence is running.
+    current: Option<usize>,
+    timer: Timer,
+    /// Rings at each transition and when the sequence is over.
+    alert: Option<Alert>,
+    /// Where the sequences were read from; `None` if they were not read from files.
+    dir: Option<PathBuf>,
+    /// Feedback for the last load, e.g. a parse error.
+    message: Option<String>,
+}
+#[derive(Debug, Clone, PartialEq)]
+struct Sequence {
+    name: String,
+    segments: Vec<Segment>,
+}
+#[derive(Debug, Clone, PartialEq)]
+pub struct Segment {
+    label: String,
+    duration: chrono::TimeDelta,
+}
+
+impl Default for SequenceState {
+    fn default() -> SequenceState {
+        SequenceState {
+            sequences: vec![],
+            selected: 0,
+            current: None,
+            timer: Timer::new("Sequence".to_string()),
+            alert: None,
+            dir: None,
+            message: None,
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum ParseSequenceError {
+    Empty,
+    /// A segment that could not be read, with its line number.
+    Invalid(usize, String),
+    Duration(usize, ParseDurationError),
+    /// A parenthesis without its pair, with its line number.
+    Unbalanced(usize),
+    /// `xN` at the end of a comma-separated list, which would only repeat its last segment.
+    RepeatAfterList(usize, String),
+}
+impl std::fmt::Display for ParseSequenceError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ParseSequenceError::Empty => write!(f, "empty sequence"),
+            ParseSequenceError::Invalid(line, s) => write!(f, "line {line}: invalid segment: {s}"),
+            ParseSequenceError::Duration(line, e) => write!(f, "line {line}: {e}"),
+            ParseSequenceError::Unbalanced(line) => {
+                write!(f, "line {line}: unbalanced parentheses")
+            }
+            ParseSequenceError::RepeatAfterList(line, s) => write!(
+                f,
+                "line {line}: {s} would only repeat itself; put the segments to repeat in \
+                 parentheses, e.g. (Work 40s, Rest 20s) x8"
+            ),
+        }
+    }
+}
+impl std::error::Error for ParseSequenceError {}
+
+/// Reads a repeat count such as `x8`.
+fn parse_repeat(token: &str) -> Option<usize> {
+    token
+        .strip_prefix(['x', '×'])
+        .and_then(|n| n.parse().ok())
+        .filter(|&n| n > 0)
+}
+
+/// The largest repeat count accepted.
+const MAX_REPEAT: usize = 1000;
+/// The largest number of segments a sequence may expand to.
+const MAX_SEGMENTS: usize = 10_000;
+
+/// Appends `segments` to `top` `count` times, or returns `false` if that goes over the limits.
+fn push_repeated(top: &mut Vec<Segment>, segments: &[Segment], count: usize) -> bool {
+    if count > MAX_REPEAT || top.len() + segments.len() * count > MAX_SEGMENTS {
+        return false;
+    }
+    for _ in 0..count {
+        top.extend_from_slice(segments);
+    }
+    true
+}
+
+/// Parses a sequence such as `Warmup 5m, (Work 40s, Rest 20s) x8`.
+///
+/// Segments are separated by commas or new lines, and each is a label followed by a duration in
+/// any form [`timer_tab::parse_duration`] accepts without spaces. A parenthesized group, or a
+/// segment that does not follow a comma, followed by `xN` is repeated N times, up to
+/// [`MAX_REPEAT`] times and [`MAX_SEGMENTS`] segments in all. `#` starts a comment.
+pub fn parse_sequence(text: &str) -> Result<Vec<Segment>, ParseSequenceError> {
+    // 括弧ごとにセグメントを積み、閉じたところで繰り返しを展開する
+    let mut stack: Vec<Vec<Segment>> = vec![vec![]];
+    for (i, line) in text.lines().enumerate() {
+        let line_no = i + 1;
+        let line = line.split('#').next().unwrap_or_default();
+        let mut closed: Option<Vec<Segment>> = None;
+        let mut after_comma = false;
+        let mut rest = line;
+        loop {
+            let end = rest.find([',', '(', ')']).unwrap_or(rest.len());
+            let chunk = &rest[..end];
+            let tokens: Vec<&str> = chunk.split_whitespace().collect();
+            let top = stack.last_mut().unwrap();
+            if let Some(group) = closed.take() {
+                let count = match tokens[..] {
+                    [] => 1,
+                    [token] => parse_repeat(token)
+                        .ok_or_else(|| ParseSequenceError::Invalid(line_no, chunk.trim().into()))?,
+                    _ => return Err(ParseSequenceError::Invalid(line_no, chunk.trim().into())),
+                };
+                if !push_repeated(top, &group, count) {
+                    return Err(ParseSequenceError::Invalid(line_no, chunk.trim().into()));
+                }
+            } else if let Some((&last, init)) = tokens.split_last() {
+                let (count, tokens) = match parse_repeat(last) {
+                    // "Work 40s, Rest 20s x8" の x8 は Rest にしか掛からないので受け付けない
+                    Some(_) if after_comma => {
+                        return Err(ParseSequenceError::RepeatAfterList(
+                            line_no,
+                            chunk.trim().into(),
+                        ));
+                    }
+                    Some(count) => (count, init),
+                    None => (1, &tokens[..]),
+                };
+                let Some((&duration, label)) = tokens.split_last() else {
+                    return Err(ParseSequenceError::Invalid(line_no, chunk.trim().into()));
+                };
+                let duration = timer_tab::parse_duration(duration)
+                    .map_err(|e| ParseSequenceError::Duration(line_no, e))?;
+                let label = match label.join(" ") {
+                    label if label.is_empty() => timer_tab::format_duration(duration),
+                    label => label,
+                };
+                if !push_repeated(top, &[Segment { label, duration }], count) {
+                    return Err(ParseSequenceError::Invalid(line_no, chunk.trim().into()));
+                }
+            }
+            match rest[end..].chars().next() {
+                Some('(') => {
+                    if !tokens.is_empty() {
+                        return Err(ParseSequenceError::Invalid(line_no, chunk.trim().into()));
+                    }
+                    stack.push(vec![]);
+                }
+                Some(')') => {
+                    if stack.len() < 2 {
+                        return Err(ParseSequenceError::Unbalanced(line_no));
+                    }
+                    closed = stack.pop();
+                }
+                Some(_) => {}
+                None => break,
+            }
+            after_comma = rest[end..].starts_with(',');
+            rest = &rest[end + 1..];
+        }
+    }
+    if stack.len() > 1 {
+        return Err(ParseSequenceError::Unbalanced(text.lines().count()));
+    }
+    let segments = stack.pop().unwrap();
+    if segments.is_empty() {
+        return Err(ParseSequenceError::Empty);
+    }
+    Ok(segments)
+}
+
+/// Reads every `*.txt` file in `dir` as a sequence named after the file.
+///
+/// Files that cannot be read are skipped, and the error is shown in the panel.
+pub fn load_sequences(state: &mut SequenceState, dir: PathBuf) {
+    state.sequences.clear();
+    state.message = None;
+    let mut paths: Vec<PathBuf> = match std::fs::read_dir(&dir) {
+        Ok(entries) => entries
+            .filter_map(|entry| entry.ok().map(|e| e.path()))
+            .filter(|path| path.extension().is_some_and(|ext| ext == "txt"))
+            .collect(),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => vec![],
+        Err(e) => {
+            state.message = Some(format!("Failed to read {}: {e}", dir.display()));
+            vec![]
+        }
+    };
+    paths.sort();
+    for path in paths {
+        let name = path
+            .file_stem()
+            .map(|s| s.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        let segments = std::fs::read_to_string(&path)
+            .map_err(|e| e.to_string())
+            .and_then(|text| parse_sequence(&text).map_err(|e| e.to_string()));
+        match segments {
+            Ok(segments) => state.sequences.push(Sequence { name, segments }),
+            Err(e) => state.message = Some(format!("{name}: {e}")),
+        }
+    }
+    state.dir = Some(dir);
+    select(
+        state,
+        std::cmp::min(state.selected, state.sequences.len().saturating_sub(1)),
+    );
+}
+
+/// Selects a sequence and shows its first segment, unless one is running.
+fn select(state: &mut SequenceState, index: usize) {
+    if state.current.is_some() {
+        return;
+    }
+    state.selected = index;
+    let duration = state
+        .sequences
+        .get(index)
+        .map_or(chrono::TimeDelta::zero(), |s| s.segments[0].duration);
+    timer_tab::load(&mut state.timer, duration);
+}
+
+fn segments(state: &SequenceState) -> &[Segment] {
+    state
+        .sequences
+        .get(state.selected)
+        .map_or(&[], |s| &s.segments[..])
+}
+
+/// Starts segment `index`, or ends the run if there is no such segment.
+fn start_segment(state: &mut SequenceState, index: usize, now: Instant) {
+    match segments(state).get(index) {
+        Some(segment) => {
+            let duration = segment.duration;
+            state.current = Some(index);
+            timer_tab::start(&mut state.timer, duration, now);
+        }
+        None => {
+            state.current = None;
+            timer_tab::load(&mut state.timer, chrono::TimeDelta::zero());
+        }
+    }
+}
+
+/// Updates the timer and moves on to the next segment once it expires.
+///
+/// Returns whether the bell should ring now.
+pub fn tick_sequence(state: &mut SequenceState, now: Instant, config: &Config) -> bool {
+    let mut ring = timer_tab::tick_one(&mut state.timer, now, config);
+    if let Some(alert) = timer_tab::take_alert(&mut state.timer) {
+        state.alert = Some(alert);
+        if let Some(index) = state.current {
+            start_segment(state, index + 1, now);
+        }
+    }
+    if let Some(alert) = &mut state.alert {
+        ring |= alert.tick(now, &config.alert);
+    }
+    ring
+}
+
+/// Whether the transition alert wants the screen inverted now.
+pub fn is_flashing(state: &SequenceState, now: Instant, config: &Config) -> bool {
+    state
+        .alert
+        .as_ref()
+        .is_some_and(|a| a.is_flashing(now, &config.alert))
+}
+
+pub fn update_sequence(state: &SequenceState, now: Instant, config: &Config) -> super::DisplayData {
+    let segments = segments(state);
+    let block_title = match state.current {
+        Some(index) if timer_tab::is_running(&state.timer) => segments[index].label.clone(),
+        Some(_) => timer_tab::status_name(&state.timer, now, config).to_string(),
+        None if state.alert.is_some() => "Done".to_string(),
+        None => "Idle".to_string(),
+    };
+    let mut display = timer_tab::display_timer(&state.timer, block_title);
+    // リングはシーケンス全体の進み具合を表す
+    if let Some(index) = state.current {
+        let total: chrono::TimeDelta = segments.iter().map(|s| s.duration).sum();
+        let done: chrono::TimeDelta = segments[..index].iter().map(|s| s.duration).sum();
+        let current =
+            segments[index].duration.num_milliseconds() as f64 * timer_tab::progress(&state.timer);
+        display.analog_state.ring = Some(super::analog::Ring {
+            fraction: (done.num_milliseconds() as f64 + current)
+                / total.num_milliseconds().max(1) as f64,
+            color: Color::Cyan,
+        });
+    }
+    display
+}
+
+pub fn handle_key_event(state: &mut SequenceState, key: &KeyEvent, now: Instant) {
+    match (key.modifiers, key.code) {
+        (_, KeyCode::Up) => select(state, state.selected.saturating_sub(1)),
+        (_, KeyCode::Down) => select(
+            state,
+            std::cmp::min(state.selected + 1, state.sequences.len().saturating_sub(1)),
+        ),
+        (_, KeyCode::Enter) if state.current.is_none() => {
+            state.alert = None;
+            start_segment(state, 0, now);
+        }
+        (_, KeyCode::Char(' ')) => {
+            state.alert = None;
+            if state.current.is_some() {
+                timer_tab::toggle(&mut state.timer, now);
+            } else {
+                start_segment(state, 0, now);
+            }
+        }
+        (_, KeyCode::Char('n')) => {
+            if let Some(index) = state.current {
+                state.alert = None;
+                start_segment(state, index + 1, now);
+            }
+        }
+        (_, KeyCode::Char('d')) => {
+            if let Some(alert) = &mut state.alert {
+                alert.dismiss();
+            }
+        }
+        (_, KeyCode::Char('r')) => {
+            state.alert = None;
+            state.current = None;
+            select(state, state.selected);
+        }
+        (_, KeyCode::Char('l')) if state.current.is_none() => {
+            if let Some(dir) = state.dir.clone() {
+                load_sequences(state, dir);
+            }
+        }
+        _ => {}
+    }
+}
+
+pub fn render_panel(
+    frame: &mut Frame,
+    panel_area: Rect,
+    state: &SequenceState,
+    now: Instant,
+    config: &Config,
+) {
+    let panel_layout = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints(vec![
+            Constraint::Min(0),
+            Constraint::Length(1),
+            Constraint::Length(1),
+            Constraint::Length(1),
+            Constraint::Length(std::cmp::max(state.sequences.len(), 1) as u16),
+            Constraint::Length(1),
+            Constraint::Length(1),
+            Constraint::Length(1),
+            Constraint::Length(1),
+            Constraint::Min(0),
+        ])
+        .split(panel_area);
+
+    frame.render_widget(
+        Text::from("[Enter]: Run  [Space]: Pause  [r]: Stop")
+            .add_modifier(Modifier::DIM)
+            .centered(),
+        panel_layout[1],
+    );
+    frame.render_widget(
+        Text::from("↑↓: Select  [n]: Next  [l]: Reload")
+            .add_modifier(Modifier::DIM)
+            .centered(),
+        panel_layout[2],
+    );
+
+    if state.sequences.is_empty() {
+        let text = match &state.dir {
+            Some(dir) => format!("No sequences in {}", dir.display()),
+            None => "No sequences".to_string(),
+        };
+        frame.render_widget(
+            Text::from(text).add_modifier(Modifier::ITALIC).centered(),
+            panel_layout[4],
+        );
+    } else {
+        let lines: Vec<Line> = state
+            .sequences
+            .iter()
+            .enumerate()
+            .map(|(i, sequence)| {
+                let total: chrono::TimeDelta = sequence.segments.iter().map(|s| s.duration).sum();
+                let line = Line::from(format!(
+                    "{} {:<16.16} {:>3} steps {:>8}",
+                    if i == state.selected { ">" } else { " " },
+                    sequence.name,
+                    sequence.segments.len(),
+                    timer_tab::format_duration(total)
+                ));
+                if i == state.selected {
+                    line.add_modifier(Modifier::BOLD)
+                } else {
+                    line.add_modifier(Modifier::DIM)
+                }
+            })
+            .collect();
+        frame.render_widget(Text::from(lines).centered(), panel_layout[4]);
+    }
+
+    let segments = segments(state);
+    if let Some(index) = state.current {
+        frame.render_widget(
+            Text::from(format!(
+                "Now: {} ({}/{})",
+                segments[index].label,
+                index + 1,
+                segments.len()
+            ))
+            .add_modifier(Modifier::BOLD)
+            .centered(),
+            panel_layout[6],
+        );
+        let next = match segments.get(index + 1) {
+            Some(next) => format!(
+                "Next: {} {}",
+                next.label,
+                timer_tab::format_duration(next.duration)
+            ),
+            None => "Next: Done".to_string(),
+        };
+        frame.render_widget(
+            Text::from(next).add_modifier(Modifier::DIM).centered(),
+            panel_layout[7],
+        );
+    }
+    let active = state
+        .alert
+        .as_ref()
+        .is_some_and(|a| a.is_active(now, &config.alert));
+    if let Some(message) = &state.message {
+        frame.render_widget(
+            Text::from(&message[..])
+                .add_modifier(Modifier::ITALIC)
+                .centered(),
+            panel_layout[8],
+        );
+    } else if active {
+        frame.render_widget(
+            Text::from("[d]: Dismiss")
+                .add_modifier(Modifier::ITALIC)
+                .centered(),
+            panel_layout[8],
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crossterm::event::KeyModifiers;
+    use std::time::Duration;
+
+    fn press(state: &mut SequenceState, code: KeyCode, now: Instant) {
+        handle_key_event(state, &KeyEvent::new(code, KeyModifiers::NONE), now);
+    }
+
+    fn segment(label: &str, secs: i64) -> Segment {
+        Segment {
+            label: label.to_string(),
+            duration: chrono::TimeDelta::seconds(secs),
+        }
+    }
+
+    #[test]
+    fn parses_sequences() {
+        assert_eq!(
+            parse_sequence("Warmup 5m, (Work 40s, Rest 20s) x2\nCool down 1:30 # easy"),
+            Ok(vec![
+                segment("Warmup", 300),
+                segment("Work", 40),
+                segment("Rest", 20),
+                segment("Work", 40),
+                segment("Rest", 20),
+                segment("Cool down", 90),
+            ])
+        );
+        assert_eq!(
+            parse_sequence("Intro 2m\nRest 20s x3\n\n(\n  Talk 5m\n) x2"),
+            Ok(vec![
+                segment("Intro", 120),
+                segment("Rest", 20),
+                segment("Rest", 20),
+                segment("Rest", 20),
+                segment("Talk", 300),
+                segment("Talk", 300),
+            ])
+        );
+        assert_eq!(parse_sequence("90s"), Ok(vec![segment("1:30", 90)]));
+        assert_eq!(parse_sequence(" # nothing"), Err(ParseSequenceError::Empty));
+        assert_eq!(
+            parse_sequence("Work 5m\nRest"),
+            Err(ParseSequenceError::Duration(
+                2,
+                ParseDurationError::Invalid("Rest".to_string())
+            ))
+        );
+        assert_eq!(
+            parse_sequence("(Work 5m"),
+            Err(ParseSequenceError::Unbalanced(1))
+        );
+        assert_eq!(
+            parse_sequence("Work 5m)"),
+            Err(ParseSequenceError::Unbalanced(1))
+        );
+        assert!(parse_sequence("(Work 5m) twice").is_err());
+        assert!(parse_sequence("Rest 20s x0").is_err());
+        assert_eq!(
+            parse_sequence("Warmup 5m, Work 40s, Rest 20s x8"),
+            Err(ParseSequenceError::RepeatAfterList(
+                1,
+                "Rest 20s x8".to_string()
+            ))
+        );
+        assert_eq!(
+            parse_sequence("Warmup 5m, (Work 40s, Rest 20s) x8")
+                .unwrap()
+                .len(),
+            17
+        );
+
+        // 大きすぎる繰り返しで展開しきれなくならない
+        assert_eq!(
+            parse_sequence("Rest 1s x99999999999"),
+            Err(ParseSequenceError::Invalid(
+                1,
+                "Rest 1s x99999999999".to_string()
+            ))
+        );
+        assert_eq!(parse_sequence("Rest 1s x1000").unwrap().len(), 1000);
+        assert_eq!(
+            parse_sequence("((Work 1s, Rest 1s) x100) x100"),
+            Err(ParseSequenceError::Invalid(1, "x100".to_string()))
+        );
+    }
+
+    #[test]
+    fn runs_segments_back_to_back() {
+        let start = Instant::now();
+        let at = |secs| start + Duration::from_secs(secs);
+        let config = Config::default();
+        let mut state = SequenceState {
+            sequences: vec![Sequence {
+                name: "HIIT".to_string(),
+                segments: parse_sequence("Work 40s, Rest 20s").unwrap(),
+            }],
+            ..Default::default()
+        };
+        select(&mut state, 0);
+        assert_eq!(
+            update_sequence(&state, at(0), &config).block_content,
+            "0:00:40.000"
+        );
+        press(&mut state, KeyCode::Enter, at(0));
+        tick_sequence(&mut state, at(10), &config);
+        let display = update_sequence(&state, at(10), &config);
+        assert_eq!(display.block_title, "Work");
+        assert_eq!(display.block_content, "0:00:30.000");
+        assert!((display.analog_state.ring.unwrap().fraction - 10. / 60.).abs() < 1e-9);
+
+        assert!(tick_sequence(&mut state, at(41), &config));
+        assert_eq!(state.current, Some(1));
+        let display = update_sequence(&state, at(41), &config);
+        assert_eq!(display.block_title, "Rest");
+        assert_eq!(display.block_content, "0:00:20.000");
+
+        press(&mut state, KeyCode::Char('d'), at(42));
+        assert!(tick_sequence(&mut state, at(62), &config));
+        assert_eq!(state.current, None);
+        assert_eq!(update_sequence(&state, at(62), &config).block_title, "Done");
+    }
+
+    #[test]
+    fn loads_sequence_files() {
+        let dir = std::env::temp_dir().join(format!("atclock-sequences-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("b.txt"), "(Work 40s, Rest 20s) x8").unwrap();
+        std::fs::write(dir.join("a.txt"), "Agenda 5m\nDemo 10m").unwrap();
+        std::fs::write(dir.join("broken.txt"), "Work").unwrap();
+        std::fs::write(dir.join("notes.md"), "ignored").unwrap();
+        let mut state = SequenceState::default();
+        load_sequences(&mut state, dir.clone());
+        let names: Vec<&str> = state.sequences.iter().map(|s| &s.name[..]).collect();
+        assert_eq!(names, vec!["a", "b"]);
+        assert_eq!(state.sequences[1].segments.len(), 16);
+        assert_eq!(state.sequences[1].segments[2], segment("Work", 40));
+        assert_eq!(
+            state.message.as_deref(),
+            Some("broken: line 1: invalid duration: Work")
+        );
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
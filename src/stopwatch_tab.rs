@@ -1,48 +1,104 @@
-use std::f64::consts::PI;
+use std::{f64::consts::PI, time::Instant};
 
 use crossterm::event::{KeyCode, KeyEvent};
 use ratatui::{
     Frame,
     layout::{Constraint, Direction, Layout, Rect},
-    style::{Modifier, Stylize},
-    text::Text,
+    style::{Color, Modifier, Stylize},
+    text::{Line, Text},
 };
 
+use super::input::{InputEvent, InputLine};
+
+/// State of the stopwatch tab: stopwatches that run independently, and the one being shown.
 #[derive(Debug)]
 pub struct StopWatchState {
-    start_time: Option<chrono::DateTime<chrono::Local>>,
-    stop_time: Option<chrono::DateTime<chrono::Local>>,
-    lap_time: Vec<chrono::DateTime<chrono::Local>>,
-    running: bool,
+    /// Never empty.
+    watches: Vec<StopWatch>,
+    selected: usize,
+    /// Label of a new stopwatch being typed.
+    input: Option<InputLine>,
+}
+#[derive(Debug, Default)]
+struct StopWatch {
+    label: String,
+    /// Start of the current run segment.
+    segment_start: Option<Instant>,
+    /// When the stopwatch was first started since the last reset.
+    first_start: Option<Instant>,
+    /// Elapsed time of the finished run segments.
+    accumulated: chrono::TimeDelta,
+    /// Total elapsed time at each lap.
+    lap_time: Vec<chrono::TimeDelta>,
+    status: StopWatchStatus,
+}
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+enum StopWatchStatus {
+    #[default]
+    Idle,
+    Running,
+    Paused,
 }
 impl Default for StopWatchState {
     fn default() -> StopWatchState {
         StopWatchState {
-            start_time: None,
-            stop_time: None,
-            lap_time: vec![],
-            running: false,
+            watches: vec![StopWatch::new("StopWatch 1".to_string())],
+            selected: 0,
+            input: None,
+        }
+    }
+}
+impl StopWatch {
+    fn new(label: String) -> StopWatch {
+        StopWatch {
+            label,
+            ..Default::default()
         }
     }
 }
 
-pub fn update_sw(state: &StopWatchState) -> super::DisplayData {
-    let stop_time = state.stop_time.or(Some(chrono::Local::now())).unwrap();
-    let elapsed = match state.start_time {
-        Some(t) => stop_time - t,
-        _ => chrono::TimeDelta::zero(),
-    };
-    let block_title = if state.running {
-        "Started".to_string()
-    } else {
-        "Idle".to_string()
-    };
-    let block_content = format!(
+fn selected(state: &StopWatchState) -> &StopWatch {
+    &state.watches[state.selected]
+}
+
+fn elapsed(sw: &StopWatch, now: Instant) -> chrono::TimeDelta {
+    match sw.segment_start {
+        Some(t) => {
+            sw.accumulated
+                + chrono::TimeDelta::from_std(now.saturating_duration_since(t)).unwrap_or_default()
+        }
+        None => sw.accumulated,
+    }
+}
+
+fn format_elapsed(elapsed: chrono::TimeDelta) -> String {
+    format!(
         "{}:{:02}.{:03}",
         elapsed.num_minutes(),
         elapsed.num_seconds() % 60,
         elapsed.num_milliseconds() % 1000
-    );
+    )
+}
+
+fn status_name(sw: &StopWatch) -> &'static str {
+    match sw.status {
+        StopWatchStatus::Idle => "Idle",
+        StopWatchStatus::Running => "Running",
+        StopWatchStatus::Paused => "Paused",
+    }
+}
+
+/// Computes the readout of the selected stopwatch.
+pub fn update_sw(state: &StopWatchState, now: Instant) -> super::DisplayData {
+    let sw = selected(state);
+    let elapsed = elapsed(sw, now);
+    // 複数あるときはどれを表示しているか分かるようにする
+    let block_title = if state.watches.len() > 1 {
+        format!("{}: {}", sw.label, status_name(sw))
+    } else {
+        status_name(sw).to_string()
+    };
+    let block_content = format_elapsed(elapsed);
     let ms = (elapsed.num_milliseconds() % 1000) as f64;
     let sec = (elapsed.num_seconds() % 60) as f64 + ms / 1000.;
     let min = elapsed.num_minutes() as f64 + sec / 60.;
@@ -50,37 +106,90 @@ pub fn update_sw(state: &StopWatchState) -> super::DisplayData {
         sec_rad: ms * PI / 500.,
         min_rad: sec * PI / 30.,
         hour_rad: min * PI / 30.,
+        ring: None,
+        face: super::analog::Face::Twelve,
+        daylight: None,
     };
     super::DisplayData {
         block_title,
         block_content,
+        content_color: Color::Reset,
         analog_state,
     }
 }
 
-pub fn handle_key_event(state: &mut StopWatchState, key: &KeyEvent) {
+/// Whether a label is being typed, so that the tab should receive every key.
+pub fn is_typing(state: &StopWatchState) -> bool {
+    state.input.is_some()
+}
+
+pub fn handle_key_event(state: &mut StopWatchState, key: &KeyEvent, now: Instant) {
+    if let Some(input) = &mut state.input {
+        match input.handle_key_event(key) {
+            InputEvent::Pending => {}
+            InputEvent::Submit(text) => {
+                state.input = None;
+                let label = match text.trim() {
+                    "" => format!("StopWatch {}", state.watches.len() + 1),
+                    label => label.to_string(),
+                };
+                state.watches.push(StopWatch::new(label));
+                state.selected = state.watches.len() - 1;
+            }
+            InputEvent::Cancel => state.input = None,
+        }
+        return;
+    }
+    let sw = &mut state.watches[state.selected];
     match (key.modifiers, key.code) {
         (_, KeyCode::Char(' ')) => {
-            if state.running {
-                state.running = false;
-                state.stop_time = Some(chrono::Local::now());
+            if sw.status == StopWatchStatus::Running {
+                sw.accumulated = elapsed(sw, now);
+                sw.segment_start = None;
+                sw.status = StopWatchStatus::Paused;
             } else {
-                state.running = true;
-                state.stop_time = None;
-                state.start_time = Some(chrono::Local::now());
-                state.lap_time = vec![];
+                sw.segment_start = Some(now);
+                sw.first_start.get_or_insert(now);
+                sw.status = StopWatchStatus::Running;
             }
         }
-        (_, KeyCode::Char('m')) => {
-            if state.running {
-                state.lap_time.push(chrono::Local::now());
-            }
+        (_, KeyCode::Char('m')) if sw.status == StopWatchStatus::Running => {
+            sw.lap_time.push(elapsed(sw, now));
+        }
+        (_, KeyCode::Char('r')) => {
+            *sw = StopWatch::new(std::mem::take(&mut sw.label));
+        }
+        (_, KeyCode::Char('[')) => {
+            state.selected = state.selected.saturating_sub(1);
+        }
+        (_, KeyCode::Char(']')) => {
+            state.selected = std::cmp::min(state.selected + 1, state.watches.len() - 1);
+        }
+        (_, KeyCode::Char('n')) => {
+            state.input = Some(InputLine::new("Label:"));
+        }
+        (_, KeyCode::Char('x')) if state.watches.len() > 1 => {
+            state.watches.remove(state.selected);
+            state.selected = std::cmp::min(state.selected, state.watches.len() - 1);
         }
         _ => {}
     }
 }
 
-pub fn render_panel(frame: &mut Frame, panel_area: Rect, state: &StopWatchState) {
+pub fn render_panel(
+    frame: &mut Frame,
+    panel_area: Rect,
+    state: &StopWatchState,
+    now: Instant,
+    local_now: chrono::DateTime<chrono::Local>,
+) {
+    let sw = selected(state);
+    // 一覧は複数あるときだけ表示する
+    let list_len = if state.watches.len() > 1 {
+        state.watches.len() + 1
+    } else {
+        0
+    };
     let panel_layout = Layout::default()
         .direction(Direction::Vertical)
         .constraints(vec![
@@ -88,48 +197,84 @@ pub fn render_panel(frame: &mut Frame, panel_area: Rect, state: &StopWatchState)
             Constraint::Length(1),
             Constraint::Length(1),
             Constraint::Length(1),
-            Constraint::Length((state.lap_time.len() + 1) as u16),
+            Constraint::Length(1),
+            Constraint::Length(list_len as u16),
+            Constraint::Length((sw.lap_time.len() + 1) as u16),
             Constraint::Min(0),
         ])
         .split(panel_area);
     let lap_layout = Layout::default()
         .direction(Direction::Vertical)
-        .constraints(vec![Constraint::Length(1); state.lap_time.len() + 1])
-        .split(panel_layout[4]);
+        .constraints(vec![Constraint::Length(1); sw.lap_time.len() + 1])
+        .split(panel_layout[6]);
 
     frame.render_widget(
-        Text::from("[Space]: Start / Stop")
+        Text::from("[Space]: Start / Pause")
             .add_modifier(Modifier::DIM)
             .centered(),
         panel_layout[1],
     );
     frame.render_widget(
-        Text::from("[m]: Lap")
+        Text::from("[m]: Lap  [r]: Reset")
             .add_modifier(Modifier::DIM)
             .centered(),
         panel_layout[2],
     );
-    for i in 0..state.lap_time.len() {
+    frame.render_widget(
+        Text::from("[n]: New  [x]: Delete  [ ]: Select")
+            .add_modifier(Modifier::DIM)
+            .centered(),
+        panel_layout[3],
+    );
+    if let Some(input) = &state.input {
+        input.render(frame, panel_layout[4]);
+    } else if let Some(t) = sw.first_start {
+        // 開始時刻は表示用なので壁時計に換算する
+        let started_at = local_now
+            - chrono::TimeDelta::from_std(now.saturating_duration_since(t)).unwrap_or_default();
+        frame.render_widget(
+            Text::from(format!("Started at {}", started_at.format("%H:%M:%S")))
+                .add_modifier(Modifier::ITALIC)
+                .centered(),
+            panel_layout[4],
+        );
+    }
+    if list_len > 0 {
+        let lines: Vec<Line> = state
+            .watches
+            .iter()
+            .enumerate()
+            .map(|(i, w)| {
+                let line = Line::from(format!(
+                    "{} {:<12.12} {:>10} {:<7}",
+                    if i == state.selected { ">" } else { " " },
+                    w.label,
+                    format_elapsed(elapsed(w, now)),
+                    status_name(w)
+                ));
+                if i == state.selected {
+                    line.add_modifier(Modifier::BOLD)
+                } else {
+                    line.add_modifier(Modifier::DIM)
+                }
+            })
+            .collect();
+        frame.render_widget(Text::from(lines).centered(), panel_layout[5]);
+    }
+    for i in 0..sw.lap_time.len() {
         let elapsed = if i >= 1 {
-            state.lap_time[i] - state.lap_time[i - 1]
+            sw.lap_time[i] - sw.lap_time[i - 1]
         } else {
-            match state.start_time {
-                Some(t) => state.lap_time[i] - t,
-                _ => chrono::TimeDelta::zero(),
-            }
+            sw.lap_time[i]
         };
         render_lap_line(frame, lap_layout[i], i + 1, elapsed);
     }
-    if state.start_time.is_some() {
-        let stop_time = state.stop_time.or(Some(chrono::Local::now())).unwrap();
-        let elapsed = if state.lap_time.len() >= 1 {
-            stop_time - state.lap_time[state.lap_time.len() - 1]
-        } else {
-            match state.start_time {
-                Some(t) => stop_time - t,
-                _ => chrono::TimeDelta::zero(),
-            }
-        };
+    if sw.status != StopWatchStatus::Idle {
+        let elapsed = elapsed(sw, now)
+            - sw.lap_time
+                .last()
+                .copied()
+                .unwrap_or(chrono::TimeDelta::zero());
         render_lap_line(
             frame,
             lap_layout[lap_layout.len() - 1],
@@ -154,15 +299,97 @@ fn render_lap_line(frame: &mut Frame, lap_area: Rect, lap_num: usize, elapsed: c
         lap_line_layout[1],
     );
     frame.render_widget(
-        Text::from(format!(
-            "{}:{:02}.{:03}",
-            elapsed.num_minutes(),
-            elapsed.num_seconds() % 60,
-            elapsed.num_milliseconds() % 1000
-        ))
-        .add_modifier(Modifier::BOLD)
-        .add_modifier(Modifier::ITALIC)
-        .right_aligned(),
+        Text::from(format_elapsed(elapsed))
+            .add_modifier(Modifier::BOLD)
+            .add_modifier(Modifier::ITALIC)
+            .right_aligned(),
         lap_line_layout[2],
     );
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crossterm::event::KeyModifiers;
+    use std::time::Duration;
+
+    fn press(state: &mut StopWatchState, code: KeyCode, now: Instant) {
+        handle_key_event(state, &KeyEvent::new(code, KeyModifiers::NONE), now);
+    }
+
+    #[test]
+    fn laps_span_pauses() {
+        let start = Instant::now();
+        let at = |secs| start + Duration::from_secs(secs);
+        let mut state = StopWatchState::default();
+        press(&mut state, KeyCode::Char(' '), at(0));
+        press(&mut state, KeyCode::Char('m'), at(10));
+        press(&mut state, KeyCode::Char(' '), at(15));
+        assert_eq!(state.watches[0].status, StopWatchStatus::Paused);
+        // 一時停止中はラップを記録しない
+        press(&mut state, KeyCode::Char('m'), at(50));
+        press(&mut state, KeyCode::Char(' '), at(100));
+        press(&mut state, KeyCode::Char('m'), at(102));
+
+        assert_eq!(
+            state.watches[0].lap_time,
+            vec![
+                chrono::TimeDelta::seconds(10),
+                chrono::TimeDelta::seconds(17)
+            ]
+        );
+        let display = update_sw(&state, at(103));
+        assert_eq!(display.block_content, "0:18.000");
+        assert_eq!(display.block_title, "Running");
+    }
+
+    #[test]
+    fn stopwatches_run_independently() {
+        let start = Instant::now();
+        let at = |secs| start + Duration::from_secs(secs);
+        let mut state = StopWatchState::default();
+        press(&mut state, KeyCode::Char(' '), at(0));
+        press(&mut state, KeyCode::Char('n'), at(1));
+        for c in "Bob".chars() {
+            press(&mut state, KeyCode::Char(c), at(1));
+        }
+        press(&mut state, KeyCode::Enter, at(1));
+        press(&mut state, KeyCode::Char(' '), at(2));
+        press(&mut state, KeyCode::Char('m'), at(5));
+
+        let display = update_sw(&state, at(10));
+        assert_eq!(display.block_title, "Bob: Running");
+        assert_eq!(display.block_content, "0:08.000");
+        press(&mut state, KeyCode::Char('['), at(10));
+        assert_eq!(update_sw(&state, at(10)).block_content, "0:10.000");
+        assert!(state.watches[0].lap_time.is_empty());
+
+        press(&mut state, KeyCode::Char('x'), at(11));
+        assert_eq!(state.watches.len(), 1);
+        assert_eq!(state.watches[0].label, "Bob");
+        assert_eq!(update_sw(&state, at(11)).block_title, "Running");
+    }
+
+    #[test]
+    fn reset_clears_laps() {
+        let start = Instant::now();
+        let mut state = StopWatchState::default();
+        press(&mut state, KeyCode::Char(' '), start);
+        press(
+            &mut state,
+            KeyCode::Char('m'),
+            start + Duration::from_secs(1),
+        );
+        press(
+            &mut state,
+            KeyCode::Char('r'),
+            start + Duration::from_secs(2),
+        );
+        assert!(state.watches[0].lap_time.is_empty());
+        assert_eq!(state.watches[0].status, StopWatchStatus::Idle);
+        assert_eq!(
+            update_sw(&state, start + Duration::from_secs(3)).block_content,
+            "0:00.000"
+        );
+    }
+}
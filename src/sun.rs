@@ -0,0 +1,220 @@
+use std::f64::consts::PI;
+
+use chrono::{DateTime, Datelike, NaiveDate, TimeZone, Timelike, Utc};
+use serde::Deserialize;
+
+/// The `[location]` table of the config, in degrees; north and east are positive.
+#[derive(Debug, Clone, Copy, Deserialize)]
+pub struct Location {
+    pub latitude: f64,
+    pub longitude: f64,
+}
+
+/// Checks that the location is on the globe; NaN is rejected too.
+pub fn check_location(location: &Location) -> color_eyre::Result<()> {
+    if !(-90. ..=90.).contains(&location.latitude) {
+        color_eyre::eyre::bail!("latitude {} is not within -90 to 90", location.latitude);
+    }
+    if !(-180. ..=180.).contains(&location.longitude) {
+        color_eyre::eyre::bail!("longitude {} is not within -180 to 180", location.longitude);
+    }
+    Ok(())
+}
+
+/// When the sun crosses an altitude on a day.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Crossing {
+    Rises {
+        rise: DateTime<Utc>,
+        set: DateTime<Utc>,
+    },
+    /// Stays above the altitude all day, as in a polar day.
+    AlwaysAbove,
+    /// Stays below the altitude all day, as in a polar night.
+    AlwaysBelow,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SunTimes {
+    /// Sunrise and sunset.
+    pub sun: Crossing,
+    /// Civil dawn and dusk, when the sun is 6° below the horizon.
+    pub civil: Crossing,
+}
+
+/// Zenith angle of sunrise and sunset, with refraction and the size of the sun.
+const SUNRISE_ZENITH: f64 = 90.833;
+const CIVIL_ZENITH: f64 = 96.;
+
+/// Computes when the sun crosses `zenith` on `date`, with the NOAA general solar position
+/// equations; the results are within a couple of minutes.
+fn crossing(date: NaiveDate, location: &Location, zenith: f64) -> Crossing {
+    let days_in_year = if date.leap_year() { 366. } else { 365. };
+    let gamma = 2. * PI / days_in_year * (date.ordinal0() as f64);
+    // 均時差 (分) と赤緯 (ラジアン)
+    let eqtime = 229.18
+        * (0.000075 + 0.001868 * gamma.cos()
+            - 0.032077 * gamma.sin()
+            - 0.014615 * (2. * gamma).cos()
+            - 0.040849 * (2. * gamma).sin());
+    let decl = 0.006918 - 0.399912 * gamma.cos() + 0.070257 * gamma.sin()
+        - 0.006758 * (2. * gamma).cos()
+        + 0.000907 * (2. * gamma).sin()
+        - 0.002697 * (3. * gamma).cos()
+        + 0.00148 * (3. * gamma).sin();
+    let lat = location.latitude.to_radians();
+    let cos_ha = zenith.to_radians().cos() / (lat.cos() * decl.cos()) - lat.tan() * decl.tan();
+    if cos_ha > 1. {
+        return Crossing::AlwaysBelow;
+    }
+    if cos_ha < -1. {
+        return Crossing::AlwaysAbove;
+    }
+    let ha = cos_ha.acos().to_degrees();
+    let midnight = Utc.from_utc_datetime(&date.and_hms_opt(0, 0, 0).unwrap());
+    let at = |minutes: f64| midnight + chrono::TimeDelta::seconds((minutes * 60.).round() as i64);
+    Crossing::Rises {
+        rise: at(720. - 4. * (location.longitude + ha) - eqtime),
+        set: at(720. - 4. * (location.longitude - ha) - eqtime),
+    }
+}
+
+pub fn sun_times(date: NaiveDate, location: &Location) -> SunTimes {
+    SunTimes {
+        sun: crossing(date, location, SUNRISE_ZENITH),
+        civil: crossing(date, location, CIVIL_ZENITH),
+    }
+}
+
+/// Time between sunrise and sunset.
+pub fn day_length(times: &SunTimes) -> chrono::TimeDelta {
+    match times.sun {
+        Crossing::Rises { rise, set } => set - rise,
+        Crossing::AlwaysAbove => chrono::TimeDelta::days(1),
+        Crossing::AlwaysBelow => chrono::TimeDelta::zero(),
+    }
+}
+
+/// Lines describing the sun's times, in the time zone `tz`.
+pub fn describe<Tz: TimeZone>(times: &SunTimes, tz: &Tz) -> Vec<String>
+where
+    Tz::Offset: std::fmt::Display,
+{
+    let hm = |t: DateTime<Utc>| t.with_timezone(tz).format("%H:%M").to_string();
+    let length = day_length(times);
+    let mut lines = vec![match times.sun {
+        Crossing::Rises { rise, set } => format!(
+            "Sunrise {}  Sunset {}  ({}h {:02}m)",
+            hm(rise),
+            hm(set),
+            length.num_hours(),
+            length.num_minutes() % 60
+        ),
+        Crossing::AlwaysAbove => "The sun does not set today".to_string(),
+        Crossing::AlwaysBelow => "The sun does not rise today".to_string(),
+    }];
+    if let Crossing::Rises { rise, set } = times.civil {
+        lines.push(format!("Twilight {} - {}", hm(rise), hm(set)));
+    }
+    lines
+}
+
+/// Angle on a 24-hour dial, clockwise from midnight at the top.
+fn dial_angle<Tz: TimeZone>(t: DateTime<Utc>, tz: &Tz) -> f64 {
+    let t = t.with_timezone(tz);
+    (t.num_seconds_from_midnight() as f64 / 3600.) * PI / 12.
+}
+
+/// Start and end angles of the daylight arc on a 24-hour dial showing `now`.
+pub fn daylight_arc<Tz: TimeZone>(now: &DateTime<Tz>, location: &Location) -> Option<(f64, f64)> {
+    match sun_times(now.date_naive(), location).sun {
+        Crossing::Rises { rise, set } => Some((
+            dial_angle(rise, &now.timezone()),
+            dial_angle(set, &now.timezone()),
+        )),
+        Crossing::AlwaysAbove => Some((0., 2. * PI)),
+        Crossing::AlwaysBelow => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TOKYO: Location = Location {
+        latitude: 35.68,
+        longitude: 139.77,
+    };
+
+    fn utc(y: i32, m: u32, d: u32, h: u32, mi: u32) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(y, m, d, h, mi, 0).unwrap()
+    }
+
+    /// Whether two times are within two minutes of each other.
+    fn near(a: DateTime<Utc>, b: DateTime<Utc>) -> bool {
+        (a - b).num_seconds().abs() <= 120
+    }
+
+    #[test]
+    fn sunrise_in_tokyo() {
+        // 国立天文台: 2025-06-21 の東京の日の出 4:25、日の入り 19:00 (JST)
+        let times = sun_times(NaiveDate::from_ymd_opt(2025, 6, 21).unwrap(), &TOKYO);
+        let Crossing::Rises { rise, set } = times.sun else {
+            panic!("{times:?}");
+        };
+        assert!(near(rise, utc(2025, 6, 20, 19, 25)), "{rise}");
+        assert!(near(set, utc(2025, 6, 21, 10, 0)), "{set}");
+        let Crossing::Rises { rise: dawn, .. } = times.civil else {
+            panic!("{times:?}");
+        };
+        assert!(dawn < rise);
+
+        let tokyo = chrono::FixedOffset::east_opt(9 * 3600).unwrap();
+        let lines = describe(&times, &tokyo);
+        assert!(lines[0].starts_with("Sunrise 04:2"), "{lines:?}");
+        assert!(lines[0].ends_with("(14h 35m)") || lines[0].ends_with("(14h 34m)"));
+        assert!(lines[1].starts_with("Twilight 03:5"), "{lines:?}");
+    }
+
+    #[test]
+    fn polar_day_and_night() {
+        let tromso = Location {
+            latitude: 69.65,
+            longitude: 18.96,
+        };
+        let summer = sun_times(NaiveDate::from_ymd_opt(2025, 6, 21).unwrap(), &tromso);
+        assert_eq!(summer.sun, Crossing::AlwaysAbove);
+        assert_eq!(day_length(&summer), chrono::TimeDelta::days(1));
+        let winter = sun_times(NaiveDate::from_ymd_opt(2025, 12, 21).unwrap(), &tromso);
+        assert_eq!(winter.sun, Crossing::AlwaysBelow);
+        assert!(matches!(winter.civil, Crossing::Rises { .. }));
+        assert_eq!(describe(&winter, &Utc)[0], "The sun does not rise today");
+    }
+
+    #[test]
+    fn rejects_locations_off_the_globe() {
+        assert!(check_location(&TOKYO).is_ok());
+        for (latitude, longitude) in [(91., 0.), (0., 1e20), (f64::NAN, 0.), (0., -180.5)] {
+            assert!(
+                check_location(&Location {
+                    latitude,
+                    longitude
+                })
+                .is_err()
+            );
+        }
+    }
+
+    #[test]
+    fn arc_on_the_dial() {
+        let london = Location {
+            latitude: 51.5,
+            longitude: 0.,
+        };
+        let now = utc(2025, 3, 20, 12, 0);
+        let (start, end) = daylight_arc(&now, &london).unwrap();
+        // 春分の日の出と日の入りはおよそ 6 時と 18 時
+        assert!((start - PI / 2.).abs() < 0.05, "{start}");
+        assert!((end - 3. * PI / 2.).abs() < 0.05, "{end}");
+    }
+}
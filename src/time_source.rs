@@ -0,0 +1,89 @@
+use std::time::Instant;
+
+/// Where the app gets the current time from.
+///
+/// Everything that depends on "now" reads it through this trait so that tests can substitute
+/// [`FakeTimeSource`] and run at fixed instants.
+pub trait TimeSource {
+    /// Monotonic time, used to measure durations.
+    fn instant(&self) -> Instant;
+    /// Wall-clock time, used for display.
+    fn local(&self) -> chrono::DateTime<chrono::Local>;
+    /// Called once per main loop iteration.
+    fn tick(&mut self) {}
+}
+
+impl TimeSource for super::monotonic::MonotonicClock {
+    fn instant(&self) -> Instant {
+        self.now()
+    }
+    fn local(&self) -> chrono::DateTime<chrono::Local> {
+        chrono::Local::now()
+    }
+    fn tick(&mut self) {
+        super::monotonic::MonotonicClock::tick(self);
+    }
+}
+
+/// A [`TimeSource`] that only moves when [`FakeTimeSource::advance`] is called.
+#[cfg(test)]
+#[derive(Debug, Clone)]
+pub struct FakeTimeSource {
+    instant: Instant,
+    local: chrono::DateTime<chrono::Local>,
+}
+#[cfg(test)]
+impl FakeTimeSource {
+    pub fn new(local: chrono::DateTime<chrono::Local>) -> FakeTimeSource {
+        FakeTimeSource {
+            instant: Instant::now(),
+            local,
+        }
+    }
+    pub fn at(year: i32, month: u32, day: u32, hour: u32, min: u32, sec: u32) -> FakeTimeSource {
+        use chrono::TimeZone;
+        FakeTimeSource::new(
+            chrono::Local
+                .with_ymd_and_hms(year, month, day, hour, min, sec)
+                .earliest()
+                .unwrap(),
+        )
+    }
+    /// The same as [`FakeTimeSource::at`], but given in UTC so that tests do not depend on the
+    /// machine's time zone.
+    pub fn at_utc(
+        year: i32,
+        month: u32,
+        day: u32,
+        hour: u32,
+        min: u32,
+        sec: u32,
+    ) -> FakeTimeSource {
+        use chrono::TimeZone;
+        FakeTimeSource::new(
+            chrono::Utc
+                .with_ymd_and_hms(year, month, day, hour, min, sec)
+                .unwrap()
+                .with_timezone(&chrono::Local),
+        )
+    }
+    pub fn advance(&mut self, d: std::time::Duration) {
+        self.instant += d;
+        self.local += d;
+    }
+}
+#[cfg(test)]
+impl Default for FakeTimeSource {
+    fn default() -> FakeTimeSource {
+        FakeTimeSource::at(2025, 1, 1, 12, 0, 0)
+    }
+}
+#[cfg(test)]
+impl TimeSource for FakeTimeSource {
+    fn instant(&self) -> Instant {
+        self.instant
+    }
+    fn local(&self) -> chrono::DateTime<chrono::Local> {
+        self.local
+    }
+}
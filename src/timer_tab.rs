@@ -1,121 +1,508 @@
-use std::f64::consts::PI;
+use std::{f64::consts::PI, path::PathBuf, time::Instant};
 
 use crossterm::event::{KeyCode, KeyEvent};
 use ratatui::{
     Frame,
     layout::{Constraint, Direction, Layout, Rect},
-    style::{Modifier, Stylize},
-    text::Text,
+    style::{Color, Modifier, Stylize},
+    text::{Line, Text},
 };
+use serde::{Deserialize, Serialize};
 
+use super::alert::Alert;
+use super::config::Config;
+use super::input::{InputEvent, InputLine};
+
+/// State of the timer tab: a list of timers that all run at once, and the one being shown.
 #[derive(Debug)]
 pub struct TimerState {
-    end_time: Option<chrono::DateTime<chrono::Local>>,
-    last_bell_time: Option<chrono::DateTime<chrono::Local>>,
+    /// Never empty.
+    timers: Vec<Timer>,
+    selected: usize,
+    input: Option<(InputKind, InputLine)>,
+    /// Feedback for the last input, e.g. a parse error.
+    message: Option<String>,
+    presets: Vec<Preset>,
+    /// Where presets are saved; `None` keeps them in memory only.
+    presets_path: Option<PathBuf>,
+    /// Why the presets file could not be read in full, and so is not saved over.
+    presets_error: Option<String>,
+    /// Selected preset while the preset picker is open.
+    picker: Option<usize>,
+}
+/// A single named countdown with its own alert.
+#[derive(Debug)]
+pub struct Timer {
+    label: String,
+    end_time: Option<Instant>,
+    /// Set once the timer expires.
+    alert: Option<Alert>,
+    /// Time since expiry in overtime mode; kept after the timer is stopped.
+    overtime: Option<chrono::TimeDelta>,
     duration: chrono::TimeDelta,
     remaining: chrono::TimeDelta,
     cursor_pos: CursorPos,
-    running: bool,
+    status: TimerStatus,
+    /// Digits typed so far, read as `HHMMSS` like on a microwave.
+    entry: Option<u32>,
+}
+#[derive(Debug, Clone, Copy)]
+enum InputKind {
+    Duration,
+    PresetName,
+    Label,
+}
+
+/// A named duration that can be loaded into the timer.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Preset {
+    name: String,
+    duration: chrono::TimeDelta,
+}
+/// On-disk format of the presets file.
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct PresetFile {
+    #[serde(default)]
+    preset: Vec<PresetEntry>,
+}
+#[derive(Debug, Serialize, Deserialize)]
+struct PresetEntry {
+    name: String,
+    /// Anything [`parse_duration`] accepts.
+    duration: String,
+}
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum TimerStatus {
+    Idle,
+    Running,
+    /// Stopped before expiry; resumes from `remaining`.
+    Paused,
 }
 #[derive(Debug, Clone, Copy)]
 enum CursorPos {
+    Hour1,
+    Min10,
     Min1,
     Sec10,
     Sec1,
 }
+
+/// The longest duration the editor accepts.
+const MAX_DURATION: chrono::TimeDelta = chrono::TimeDelta::seconds(99 * 3600 + 59 * 60 + 59);
 impl Default for TimerState {
     fn default() -> TimerState {
         TimerState {
+            timers: vec![Timer::new("Timer 1".to_string())],
+            selected: 0,
+            input: None,
+            message: None,
+            presets: vec![],
+            presets_path: None,
+            presets_error: None,
+            picker: None,
+        }
+    }
+}
+impl Timer {
+    pub fn new(label: String) -> Timer {
+        Timer {
+            label,
             end_time: None,
-            last_bell_time: None,
+            alert: None,
+            overtime: None,
             duration: chrono::TimeDelta::zero(),
             remaining: chrono::TimeDelta::zero(),
             cursor_pos: CursorPos::Min1,
-            running: false,
+            status: TimerStatus::Idle,
+            entry: None,
         }
     }
 }
 
-pub fn update_timer(state: &mut TimerState) -> super::DisplayData {
-    let block_title = if state.running {
-        "Started".to_string()
-    } else {
-        "Idle".to_string()
-    };
-    if let Some(t) = state.end_time {
-        if t < chrono::Local::now() {
-            if state
-                .last_bell_time
-                .is_none_or(|t| t < chrono::Local::now() - chrono::TimeDelta::milliseconds(250))
-            {
-                print!("\x07");
-                state.last_bell_time = Some(chrono::Local::now());
+/// Settings of the `[timer]` table of the config.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct TimerConfig {
+    /// Count up past zero once a timer expires.
+    pub overtime: bool,
+}
+impl Default for TimerConfig {
+    fn default() -> TimerConfig {
+        TimerConfig { overtime: true }
+    }
+}
+
+/// Updates every timer, including the ones not shown.
+///
+/// Returns whether the bell should ring now.
+pub fn tick_timer(state: &mut TimerState, now: Instant, config: &Config) -> bool {
+    // 全てのタイマーを進めるため短絡評価しない
+    state
+        .timers
+        .iter_mut()
+        .fold(false, |ring, timer| tick_one(timer, now, config) | ring)
+}
+
+/// Updates the remaining time and starts the alert once the timer has expired.
+///
+/// Returns whether the bell should ring now.
+pub fn tick_one(timer: &mut Timer, now: Instant, config: &Config) -> bool {
+    if let Some(t) = timer.end_time {
+        if t < now {
+            timer.remaining = chrono::TimeDelta::zero();
+            if config.timer.overtime {
+                timer.overtime = Some(chrono::TimeDelta::from_std(now - t).unwrap_or_default());
             }
-            state.remaining = chrono::TimeDelta::zero();
+            return timer
+                .alert
+                .get_or_insert_with(|| Alert::new(now))
+                .tick(now, &config.alert);
         } else {
-            state.remaining = t - chrono::Local::now();
+            timer.remaining = time_until(t, now);
         }
     }
+    false
+}
+
+/// Whether an expired timer wants the screen inverted now.
+pub fn is_flashing(state: &TimerState, now: Instant, config: &Config) -> bool {
+    state.timers.iter().any(|timer| {
+        timer
+            .alert
+            .as_ref()
+            .is_some_and(|a| a.is_flashing(now, &config.alert))
+    })
+}
+
+fn time_until(t: Instant, now: Instant) -> chrono::TimeDelta {
+    chrono::TimeDelta::from_std(t.saturating_duration_since(now)).unwrap_or_default()
+}
+
+fn after(now: Instant, d: chrono::TimeDelta) -> Instant {
+    now + d.to_std().unwrap_or_default()
+}
+
+fn selected(state: &TimerState) -> &Timer {
+    &state.timers[state.selected]
+}
+
+fn selected_mut(state: &mut TimerState) -> &mut Timer {
+    &mut state.timers[state.selected]
+}
+
+pub fn status_name(timer: &Timer, now: Instant, config: &Config) -> &'static str {
+    match (timer.status, &timer.alert) {
+        (TimerStatus::Running, Some(a)) if a.is_active(now, &config.alert) => "Time's up!",
+        (TimerStatus::Running, Some(_)) => "Expired",
+        (TimerStatus::Idle, _) => "Idle",
+        (TimerStatus::Running, None) => "Started",
+        (TimerStatus::Paused, _) => "Paused",
+    }
+}
+
+/// Computes the readout of the selected timer.
+pub fn update_timer(state: &TimerState, now: Instant, config: &Config) -> super::DisplayData {
+    let timer = selected(state);
+    // タイマーが複数あるときはどれを表示しているか分かるようにする
+    let block_title = if state.timers.len() > 1 {
+        format!("{}: {}", timer.label, status_name(timer, now, config))
+    } else {
+        status_name(timer, now, config).to_string()
+    };
+    display_timer(timer, block_title)
+}
+
+/// Computes the readout of a single timer, for this tab and the ones built on it.
+pub fn display_timer(timer: &Timer, block_title: String) -> super::DisplayData {
+    // 超過時間はカウントアップして色を変えて表示する
+    let (shown, sign, content_color) = match timer.overtime {
+        Some(overtime) => (overtime, "+", Color::Red),
+        None => (timer.remaining, "", Color::Reset),
+    };
     let block_content = format!(
-        "{}:{:02}.{:03}",
-        state.remaining.num_minutes(),
-        state.remaining.num_seconds() % 60,
-        state.remaining.num_milliseconds() % 1000
+        "{}{}:{:02}:{:02}.{:03}",
+        sign,
+        shown.num_hours(),
+        shown.num_minutes() % 60,
+        shown.num_seconds() % 60,
+        shown.num_milliseconds() % 1000
     );
-    let ms = (state.remaining.num_milliseconds() % 1000) as f64;
-    let sec = (state.remaining.num_seconds() % 60) as f64 + ms / 1000.;
-    let min = state.remaining.num_minutes() as f64 + sec / 60.;
-    let analog_state = super::analog::ClockState {
-        sec_rad: ms * PI / 500.,
-        min_rad: sec * PI / 30.,
-        hour_rad: min * PI / 30.,
+    let ms = (shown.num_milliseconds() % 1000) as f64;
+    let sec = (shown.num_seconds() % 60) as f64 + ms / 1000.;
+    let analog_state = if timer.duration >= chrono::TimeDelta::hours(1) {
+        // 1時間以上のタイマーは時計と同じ針の割り当てにする
+        let min = (shown.num_minutes() % 60) as f64 + sec / 60.;
+        let hour = shown.num_hours() as f64 + min / 60.;
+        super::analog::ClockState {
+            sec_rad: sec * PI / 30.,
+            min_rad: min * PI / 30.,
+            hour_rad: hour * PI / 6.,
+            ring: None,
+            face: super::analog::Face::Twelve,
+            daylight: None,
+        }
+    } else {
+        let min = shown.num_minutes() as f64 + sec / 60.;
+        super::analog::ClockState {
+            sec_rad: ms * PI / 500.,
+            min_rad: sec * PI / 30.,
+            hour_rad: min * PI / 30.,
+            ring: None,
+            face: super::analog::Face::Twelve,
+            daylight: None,
+        }
     };
     super::DisplayData {
         block_title,
         block_content,
+        content_color,
         analog_state,
     }
 }
 
-pub fn handle_key_event(state: &mut TimerState, key: &KeyEvent) {
-    match (key.modifiers, key.code) {
-        (_, KeyCode::Char(' ')) => {
-            if state.running {
-                state.running = false;
-                state.end_time = None;
+/// Loads the presets saved in `path`, and saves them there from now on.
+///
+/// If the file cannot be read in full, the presets that could be read are kept in memory only so
+/// that saving does not overwrite the rest of the file.
+pub fn load_presets(state: &mut TimerState, path: PathBuf) {
+    match super::config::load::<PresetFile>(&path) {
+        Ok(file) => {
+            for entry in file.preset {
+                match parse_duration(&entry.duration) {
+                    Ok(duration) => state.presets.push(Preset {
+                        name: entry.name,
+                        duration,
+                    }),
+                    Err(e) => state.presets_error = Some(format!("{}: {e}", entry.name)),
+                }
+            }
+        }
+        Err(e) => state.presets_error = Some(format!("Failed to load presets: {e}")),
+    }
+    match &state.presets_error {
+        Some(e) => state.message = Some(format!("{e} (changes will not be saved)")),
+        None => state.presets_path = Some(path),
+    }
+}
+
+fn save_presets(state: &mut TimerState) {
+    let Some(path) = &state.presets_path else {
+        if let Some(e) = &state.presets_error {
+            state.message = Some(format!("Not saved: {e}"));
+        }
+        return;
+    };
+    let file = PresetFile {
+        preset: state
+            .presets
+            .iter()
+            .map(|p| PresetEntry {
+                name: p.name.clone(),
+                duration: format_duration(p.duration),
+            })
+            .collect(),
+    };
+    if let Err(e) = super::config::save(path, &file) {
+        state.message = Some(format!("Failed to save presets: {e}"));
+    }
+}
+
+/// Whether the timer tab is reading text or picking a preset, and should receive every key.
+pub fn is_typing(state: &TimerState) -> bool {
+    state.input.is_some() || state.picker.is_some()
+}
+
+fn load_preset(state: &mut TimerState, index: usize) {
+    if let Some(preset) = state.presets.get(index) {
+        let duration = preset.duration;
+        let timer = selected_mut(state);
+        timer.duration = duration;
+        timer.entry = None;
+        state.picker = None;
+    }
+}
+
+fn handle_picker_key_event(state: &mut TimerState, key: &KeyEvent, selected: usize) {
+    match key.code {
+        KeyCode::Up => state.picker = Some(selected.saturating_sub(1)),
+        KeyCode::Down => {
+            state.picker = Some(std::cmp::min(selected + 1, state.presets.len() - 1));
+        }
+        KeyCode::Enter => load_preset(state, selected),
+        KeyCode::Char(c @ '1'..='9') => load_preset(state, c.to_digit(10).unwrap() as usize - 1),
+        KeyCode::Char('d') | KeyCode::Delete => {
+            state.presets.remove(selected);
+            state.picker = if state.presets.is_empty() {
+                None
             } else {
-                state.running = true;
-                state.end_time = Some(chrono::Local::now() + state.duration);
+                Some(std::cmp::min(selected, state.presets.len() - 1))
+            };
+            save_presets(state);
+        }
+        KeyCode::Esc | KeyCode::Char('p') => state.picker = None,
+        _ => {}
+    }
+}
+
+fn handle_input_submit(state: &mut TimerState, kind: InputKind, text: String) {
+    match kind {
+        InputKind::Duration => match parse_duration(&text) {
+            Ok(d) => {
+                let timer = selected_mut(state);
+                timer.duration = d;
+                timer.entry = None;
+            }
+            Err(e) => state.message = Some(e.to_string()),
+        },
+        InputKind::PresetName => {
+            let timer = selected_mut(state);
+            timer.entry = None;
+            let duration = timer.duration;
+            let name = match text.trim() {
+                "" => format_duration(duration),
+                name => name.to_string(),
+            };
+            state.presets.push(Preset { name, duration });
+            save_presets(state);
+        }
+        InputKind::Label => {
+            let label = match text.trim() {
+                "" => format!("Timer {}", state.timers.len() + 1),
+                label => label.to_string(),
+            };
+            state.timers.push(Timer::new(label));
+            state.selected = state.timers.len() - 1;
+        }
+    }
+}
+
+pub fn handle_key_event(state: &mut TimerState, key: &KeyEvent, now: Instant, config: &Config) {
+    if let Some((kind, input)) = &mut state.input {
+        let kind = *kind;
+        match input.handle_key_event(key) {
+            InputEvent::Pending => {}
+            InputEvent::Submit(text) => {
+                state.input = None;
+                handle_input_submit(state, kind, text);
+            }
+            InputEvent::Cancel => state.input = None,
+        }
+        return;
+    }
+    if let Some(selected) = state.picker {
+        handle_picker_key_event(state, key, selected);
+        return;
+    }
+    state.message = None;
+    match (key.modifiers, key.code) {
+        (_, KeyCode::Char('[')) => {
+            state.selected = state.selected.saturating_sub(1);
+        }
+        (_, KeyCode::Char(']')) => {
+            state.selected = std::cmp::min(state.selected + 1, state.timers.len() - 1);
+        }
+        (_, KeyCode::Char('n')) => {
+            state.input = Some((InputKind::Label, InputLine::new("Label:")));
+        }
+        (_, KeyCode::Char('x')) if state.timers.len() > 1 => {
+            state.timers.remove(state.selected);
+            state.selected = std::cmp::min(state.selected, state.timers.len() - 1);
+        }
+        (_, KeyCode::Char('d')) => {
+            // 裏で鳴っているタイマーも止められるように全て止める
+            for alert in state.timers.iter_mut().filter_map(|t| t.alert.as_mut()) {
+                alert.dismiss();
             }
         }
+        (_, KeyCode::Enter) => {
+            state.input = Some((InputKind::Duration, InputLine::new("Duration:")));
+        }
+        (_, KeyCode::Char('p')) if !state.presets.is_empty() => {
+            state.picker = Some(0);
+        }
+        (_, KeyCode::Char('s')) => {
+            state.input = Some((InputKind::PresetName, InputLine::new("Preset name:")));
+        }
+        _ => handle_timer_key_event(selected_mut(state), key, now, config),
+    }
+}
+
+fn handle_timer_key_event(timer: &mut Timer, key: &KeyEvent, now: Instant, config: &Config) {
+    match (key.modifiers, key.code) {
+        (_, KeyCode::Char(' ')) => toggle(timer, now),
+        (_, KeyCode::Char('r')) => {
+            timer.status = TimerStatus::Idle;
+            timer.end_time = None;
+            timer.alert = None;
+            timer.overtime = None;
+            timer.remaining = chrono::TimeDelta::zero();
+        }
+        (_, KeyCode::Char('z')) if timer.alert.is_some() => {
+            timer.alert = None;
+            timer.overtime = None;
+            timer.end_time = Some(after(
+                now,
+                chrono::TimeDelta::minutes(config.alert.snooze_minutes as i64),
+            ));
+        }
+        (_, KeyCode::Char(c @ '0'..='9')) => {
+            let entry = timer.entry.unwrap_or(0);
+            // 6桁 (HHMMSS) を超える入力は無視する
+            if entry < 100_000 {
+                let entry = entry * 10 + c.to_digit(10).unwrap();
+                timer.entry = Some(entry);
+                timer.duration = std::cmp::min(entry_to_duration(entry), MAX_DURATION);
+            }
+        }
+        (_, KeyCode::Backspace) => {
+            let entry = timer
+                .entry
+                .unwrap_or_else(|| duration_to_entry(timer.duration))
+                / 10;
+            timer.entry = Some(entry);
+            timer.duration = entry_to_duration(entry);
+        }
         (_, KeyCode::Up) => {
-            match state.cursor_pos {
-                CursorPos::Min1 => state.duration += chrono::TimeDelta::minutes(1),
-                CursorPos::Sec10 => state.duration += chrono::TimeDelta::seconds(10),
-                CursorPos::Sec1 => state.duration += chrono::TimeDelta::seconds(1),
+            timer.entry = None;
+            match timer.cursor_pos {
+                CursorPos::Hour1 => timer.duration += chrono::TimeDelta::hours(1),
+                CursorPos::Min10 => timer.duration += chrono::TimeDelta::minutes(10),
+                CursorPos::Min1 => timer.duration += chrono::TimeDelta::minutes(1),
+                CursorPos::Sec10 => timer.duration += chrono::TimeDelta::seconds(10),
+                CursorPos::Sec1 => timer.duration += chrono::TimeDelta::seconds(1),
             };
+            if timer.duration > MAX_DURATION {
+                timer.duration = MAX_DURATION;
+            }
         }
         (_, KeyCode::Down) => {
-            match state.cursor_pos {
-                CursorPos::Min1 => state.duration -= chrono::TimeDelta::minutes(1),
-                CursorPos::Sec10 => state.duration -= chrono::TimeDelta::seconds(10),
-                CursorPos::Sec1 => state.duration -= chrono::TimeDelta::seconds(1),
+            timer.entry = None;
+            match timer.cursor_pos {
+                CursorPos::Hour1 => timer.duration -= chrono::TimeDelta::hours(1),
+                CursorPos::Min10 => timer.duration -= chrono::TimeDelta::minutes(10),
+                CursorPos::Min1 => timer.duration -= chrono::TimeDelta::minutes(1),
+                CursorPos::Sec10 => timer.duration -= chrono::TimeDelta::seconds(10),
+                CursorPos::Sec1 => timer.duration -= chrono::TimeDelta::seconds(1),
             };
-            if state.duration < chrono::TimeDelta::zero() {
-                state.duration = chrono::TimeDelta::zero();
+            if timer.duration < chrono::TimeDelta::zero() {
+                timer.duration = chrono::TimeDelta::zero();
             }
         }
         (_, KeyCode::Left) => {
-            match state.cursor_pos {
-                CursorPos::Min1 => {}
-                CursorPos::Sec10 => state.cursor_pos = CursorPos::Min1,
-                CursorPos::Sec1 => state.cursor_pos = CursorPos::Sec10,
+            match timer.cursor_pos {
+                CursorPos::Hour1 => {}
+                CursorPos::Min10 => timer.cursor_pos = CursorPos::Hour1,
+                CursorPos::Min1 => timer.cursor_pos = CursorPos::Min10,
+                CursorPos::Sec10 => timer.cursor_pos = CursorPos::Min1,
+                CursorPos::Sec1 => timer.cursor_pos = CursorPos::Sec10,
             };
         }
         (_, KeyCode::Right) => {
-            match state.cursor_pos {
-                CursorPos::Min1 => state.cursor_pos = CursorPos::Sec10,
-                CursorPos::Sec10 => state.cursor_pos = CursorPos::Sec1,
+            match timer.cursor_pos {
+                CursorPos::Hour1 => timer.cursor_pos = CursorPos::Min10,
+                CursorPos::Min10 => timer.cursor_pos = CursorPos::Min1,
+                CursorPos::Min1 => timer.cursor_pos = CursorPos::Sec10,
+                CursorPos::Sec10 => timer.cursor_pos = CursorPos::Sec1,
                 CursorPos::Sec1 => {}
             };
         }
@@ -123,57 +510,738 @@ pub fn handle_key_event(state: &mut TimerState, key: &KeyEvent) {
     }
 }
 
-pub fn render_panel(frame: &mut Frame, panel_area: Rect, state: &TimerState) {
+/// Starts, pauses or resumes the timer, or stops it if it has expired.
+pub fn toggle(timer: &mut Timer, now: Instant) {
+    match timer.status {
+        TimerStatus::Idle => {
+            timer.status = TimerStatus::Running;
+            timer.end_time = Some(after(now, timer.duration));
+            timer.entry = None;
+            timer.overtime = None;
+        }
+        TimerStatus::Running => {
+            match timer.end_time {
+                // 鳴っているタイマーは一時停止ではなく停止する
+                Some(t) if t < now => {
+                    timer.status = TimerStatus::Idle;
+                    timer.remaining = chrono::TimeDelta::zero();
+                    timer.alert = None;
+                }
+                Some(t) => {
+                    timer.status = TimerStatus::Paused;
+                    timer.remaining = time_until(t, now);
+                }
+                None => timer.status = TimerStatus::Paused,
+            }
+            timer.end_time = None;
+        }
+        TimerStatus::Paused => {
+            timer.status = TimerStatus::Running;
+            timer.end_time = Some(after(now, timer.remaining));
+        }
+    }
+}
+
+/// Stops the timer and sets it to `duration`, ready to be started.
+pub fn load(timer: &mut Timer, duration: chrono::TimeDelta) {
+    timer.status = TimerStatus::Idle;
+    timer.end_time = None;
+    timer.alert = None;
+    timer.overtime = None;
+    timer.entry = None;
+    timer.duration = duration;
+    timer.remaining = duration;
+}
+
+/// Sets the timer to `duration` and starts it at `now`.
+pub fn start(timer: &mut Timer, duration: chrono::TimeDelta, now: Instant) {
+    load(timer, duration);
+    toggle(timer, now);
+}
+
+/// Adds `by` to the timer, whether it is running or not.
+pub fn extend(timer: &mut Timer, by: chrono::TimeDelta) {
+    timer.duration = std::cmp::min(timer.duration + by, MAX_DURATION);
+    timer.remaining += by;
+    timer.end_time = timer.end_time.map(|t| after(t, by));
+}
+
+/// Takes the alert started by [`tick_one`], if the timer has expired.
+pub fn take_alert(timer: &mut Timer) -> Option<Alert> {
+    timer.alert.take()
+}
+
+/// Whether the timer is counting down or ringing.
+pub fn is_running(timer: &Timer) -> bool {
+    timer.status == TimerStatus::Running
+}
+
+/// How much of the duration has passed, from 0 to 1.
+pub fn progress(timer: &Timer) -> f64 {
+    if timer.status == TimerStatus::Idle || timer.duration <= chrono::TimeDelta::zero() {
+        return 0.;
+    }
+    let remaining = timer.remaining.num_milliseconds() as f64;
+    (1. - remaining / timer.duration.num_milliseconds() as f64).clamp(0., 1.)
+}
+
+/// When a running timer will expire, by the wall clock.
+pub fn ends_at(
+    timer: &Timer,
+    local_now: chrono::DateTime<chrono::Local>,
+) -> Option<chrono::DateTime<chrono::Local>> {
+    // 終了時刻の表示は目安なので壁時計から計算する
+    (timer.status == TimerStatus::Running && timer.remaining > chrono::TimeDelta::zero())
+        .then(|| local_now + timer.remaining)
+}
+
+pub fn render_panel(
+    frame: &mut Frame,
+    panel_area: Rect,
+    state: &TimerState,
+    now: Instant,
+    local_now: chrono::DateTime<chrono::Local>,
+    config: &Config,
+) {
+    let timer = selected(state);
+    let mut constraints = vec![Constraint::Min(0)];
+    constraints.extend([Constraint::Length(1); 12]);
+    constraints.push(Constraint::Length(state.timers.len() as u16));
+    constraints.push(Constraint::Length(1));
+    constraints.push(Constraint::Length(state.presets.len() as u16));
+    constraints.push(Constraint::Min(0));
     let panel_layout = Layout::default()
         .direction(Direction::Vertical)
-        .constraints(vec![
-            Constraint::Min(0),
-            Constraint::Length(1),
-            Constraint::Length(1),
-            Constraint::Length(1),
-            Constraint::Length(1),
-            Constraint::Length(1),
-            Constraint::Length(1),
-            Constraint::Min(0),
-        ])
+        .constraints(constraints)
         .split(panel_area);
 
     frame.render_widget(
-        Text::from("[Space]: Start / Stop")
+        Text::from("[Space]: Start / Pause")
             .add_modifier(Modifier::DIM)
             .centered(),
         panel_layout[1],
     );
     frame.render_widget(
-        Text::from("↑↓/←→: Edit time")
+        Text::from("[r]: Reset  ↑↓/←→: Edit time")
             .add_modifier(Modifier::DIM)
             .centered(),
         panel_layout[2],
     );
+    frame.render_widget(
+        Text::from("[0-9]: Type  [Enter]: Input")
+            .add_modifier(Modifier::DIM)
+            .centered(),
+        panel_layout[3],
+    );
+    frame.render_widget(
+        Text::from(if state.picker.is_some() {
+            "[1-9]/[Enter]: Load  [d]: Delete"
+        } else {
+            "[p]: Presets  [s]: Save preset"
+        })
+        .add_modifier(Modifier::DIM)
+        .centered(),
+        panel_layout[4],
+    );
+    frame.render_widget(
+        Text::from("[n]: New  [x]: Delete  [ ]: Select")
+            .add_modifier(Modifier::DIM)
+            .centered(),
+        panel_layout[5],
+    );
 
-    match state.cursor_pos {
-        CursorPos::Min1 => {
-            frame.render_widget(Text::from(" ^      ").centered(), panel_layout[4]);
-            frame.render_widget(Text::from(" v      ").centered(), panel_layout[6]);
-        }
-        CursorPos::Sec10 => {
-            frame.render_widget(Text::from("     ^  ").centered(), panel_layout[4]);
-            frame.render_widget(Text::from("     v  ").centered(), panel_layout[6]);
-        }
-        CursorPos::Sec1 => {
-            frame.render_widget(Text::from("       ^").centered(), panel_layout[4]);
-            frame.render_widget(Text::from("       v").centered(), panel_layout[6]);
-        }
+    let cursor = match timer.cursor_pos {
+        CursorPos::Hour1 => " ^            ",
+        CursorPos::Min10 => "     ^        ",
+        CursorPos::Min1 => "       ^      ",
+        CursorPos::Sec10 => "           ^  ",
+        CursorPos::Sec1 => "             ^",
     };
+    frame.render_widget(Text::from(cursor).centered(), panel_layout[7]);
+    frame.render_widget(
+        Text::from(cursor.replace('^', "v")).centered(),
+        panel_layout[9],
+    );
     frame.render_widget(
         Text::from(format!(
-            "{:2} : {} {}",
-            state.duration.num_minutes(),
-            state.duration.num_seconds() / 10 % 6,
-            state.duration.num_seconds() % 10,
+            "{:2} : {} {} : {} {}",
+            timer.duration.num_hours(),
+            timer.duration.num_minutes() / 10 % 6,
+            timer.duration.num_minutes() % 10,
+            timer.duration.num_seconds() / 10 % 6,
+            timer.duration.num_seconds() % 10,
         ))
         .add_modifier(Modifier::BOLD)
         .centered(),
-        panel_layout[5],
+        panel_layout[8],
     );
+    let ringing = state.timers.iter().find(|t| {
+        t.alert
+            .as_ref()
+            .is_some_and(|a| a.is_active(now, &config.alert))
+    });
+    if let Some((_, input)) = &state.input {
+        input.render(frame, panel_layout[11]);
+    } else if let Some(message) = &state.message {
+        frame.render_widget(
+            Text::from(&message[..])
+                .add_modifier(Modifier::ITALIC)
+                .centered(),
+            panel_layout[11],
+        );
+    } else if timer.alert.is_some() {
+        frame.render_widget(
+            Text::from("[d]: Dismiss  [z]: Snooze")
+                .add_modifier(Modifier::ITALIC)
+                .centered(),
+            panel_layout[11],
+        );
+    } else if let Some(ringing) = ringing {
+        frame.render_widget(
+            Text::from(format!("{} is up  [d]: Dismiss", ringing.label))
+                .add_modifier(Modifier::ITALIC)
+                .centered(),
+            panel_layout[11],
+        );
+    } else if let Some(end) = ends_at(timer, local_now) {
+        frame.render_widget(
+            Text::from(format!("Ends at {}", end.format("%H:%M:%S")))
+                .add_modifier(Modifier::ITALIC)
+                .centered(),
+            panel_layout[11],
+        );
+    }
+    let timer_lines: Vec<Line> = state
+        .timers
+        .iter()
+        .enumerate()
+        .map(|(i, t)| {
+            let time = match (t.overtime, t.status) {
+                (Some(overtime), _) => format!("+{}", format_duration(overtime)),
+                (None, TimerStatus::Idle) => format_duration(t.duration),
+                (None, _) => format_duration(t.remaining),
+            };
+            let line = Line::from(format!(
+                "{} {:<12.12} {:>9} {:<10}",
+                if i == state.selected { ">" } else { " " },
+                t.label,
+                time,
+                status_name(t, now, config)
+            ));
+            if i == state.selected {
+                line.add_modifier(Modifier::BOLD)
+            } else {
+                line.add_modifier(Modifier::DIM)
+            }
+        })
+        .collect();
+    frame.render_widget(Text::from(timer_lines).centered(), panel_layout[13]);
+    let preset_lines: Vec<Line> = state
+        .presets
+        .iter()
+        .enumerate()
+        .map(|(i, preset)| {
+            let line = Line::from(format!(
+                "{} {:<12.12} {:>8}",
+                if i < 9 {
+                    format!("{}.", i + 1)
+                } else {
+                    "  ".to_string()
+                },
+                preset.name,
+                format_duration(preset.duration)
+            ));
+            if state.picker == Some(i) {
+                line.add_modifier(Modifier::REVERSED)
+            } else if state.picker.is_some() {
+                line
+            } else {
+                line.add_modifier(Modifier::DIM)
+            }
+        })
+        .collect();
+    frame.render_widget(Text::from(preset_lines).centered(), panel_layout[15]);
+}
+
+/// Formats a duration as `M:SS`, or `H:MM:SS` if it is an hour or longer.
+pub fn format_duration(d: chrono::TimeDelta) -> String {
+    if d >= chrono::TimeDelta::hours(1) {
+        format!(
+            "{}:{:02}:{:02}",
+            d.num_hours(),
+            d.num_minutes() % 60,
+            d.num_seconds() % 60
+        )
+    } else {
+        format!("{}:{:02}", d.num_minutes(), d.num_seconds() % 60)
+    }
+}
+
+/// Reads typed digits as `HHMMSS`. Each field may overflow, so `90` is 90 seconds.
+fn entry_to_duration(entry: u32) -> chrono::TimeDelta {
+    chrono::TimeDelta::hours((entry / 10000) as i64)
+        + chrono::TimeDelta::minutes((entry / 100 % 100) as i64)
+        + chrono::TimeDelta::seconds((entry % 100) as i64)
+}
+
+fn duration_to_entry(d: chrono::TimeDelta) -> u32 {
+    (d.num_hours() * 10000 + d.num_minutes() % 60 * 100 + d.num_seconds() % 60) as u32
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum ParseDurationError {
+    Empty,
+    /// A part of the input that could not be read.
+    Invalid(String),
+    TooLong,
+}
+impl std::fmt::Display for ParseDurationError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ParseDurationError::Empty => write!(f, "empty duration"),
+            ParseDurationError::Invalid(s) => write!(f, "invalid duration: {s}"),
+            ParseDurationError::TooLong => write!(f, "duration too long"),
+        }
+    }
+}
+impl std::error::Error for ParseDurationError {}
+
+/// Parses a duration such as `1h30m`, `90s`, `25:00`, `1:02:03` or `25` (minutes).
+pub fn parse_duration(s: &str) -> Result<chrono::TimeDelta, ParseDurationError> {
+    let s = s.trim();
+    if s.is_empty() {
+        return Err(ParseDurationError::Empty);
+    }
+    let invalid = || ParseDurationError::Invalid(s.to_string());
+    // 負の値を受け付けないように符号なしで読む
+    let number = |part: &str| part.trim().parse::<u32>().map_err(|_| invalid());
+    let d = if s.contains(':') {
+        let parts: Vec<&str> = s.split(':').collect();
+        if parts.len() > 3 {
+            return Err(invalid());
+        }
+        // 先頭以外のフィールドは 60 未満
+        let mut total = number(parts[0])? as i64;
+        for part in &parts[1..] {
+            let n = number(part)?;
+            if n >= 60 {
+                return Err(invalid());
+            }
+            total = total * 60 + n as i64;
+        }
+        chrono::TimeDelta::try_seconds(total).ok_or(ParseDurationError::TooLong)?
+    } else if let Ok(min) = s.parse::<u32>() {
+        chrono::TimeDelta::try_minutes(min as i64).ok_or(ParseDurationError::TooLong)?
+    } else {
+        let mut total = chrono::TimeDelta::zero();
+        let mut rest = s;
+        while !rest.is_empty() {
+            let digits = rest.len() - rest.trim_start_matches(|c: char| c.is_ascii_digit()).len();
+            if digits == 0 {
+                return Err(invalid());
+            }
+            let n = number(&rest[..digits])?;
+            rest = rest[digits..].trim_start();
+            let unit_len = rest.len() - rest.trim_start_matches(|c: char| c.is_alphabetic()).len();
+            let part = match rest[..unit_len].to_lowercase().as_str() {
+                "h" | "hr" | "hour" | "hours" => chrono::TimeDelta::try_hours(n as i64),
+                "m" | "min" | "mins" | "minute" | "minutes" => {
+                    chrono::TimeDelta::try_minutes(n as i64)
+                }
+                "s" | "sec" | "secs" | "second" | "seconds" => {
+                    chrono::TimeDelta::try_seconds(n as i64)
+                }
+                _ => return Err(invalid()),
+            };
+            total = part
+                .and_then(|part| total.checked_add(&part))
+                .filter(|total| *total <= MAX_DURATION)
+                .ok_or(ParseDurationError::TooLong)?;
+            rest = rest[unit_len..].trim_start();
+        }
+        total
+    };
+    if d > MAX_DURATION {
+        return Err(ParseDurationError::TooLong);
+    }
+    Ok(d)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crossterm::event::KeyModifiers;
+    use std::time::Duration;
+
+    fn press(state: &mut TimerState, code: KeyCode, now: Instant) {
+        handle_key_event(
+            state,
+            &KeyEvent::new(code, KeyModifiers::NONE),
+            now,
+            &Config::default(),
+        );
+    }
+
+    fn tick(state: &mut TimerState, now: Instant) -> bool {
+        tick_timer(state, now, &Config::default())
+    }
+
+    #[test]
+    fn counts_down_and_expires() {
+        let start = Instant::now();
+        let mut state = TimerState::default();
+        press(&mut state, KeyCode::Up, start);
+        press(&mut state, KeyCode::Char(' '), start);
+
+        tick(&mut state, start + Duration::from_millis(15_500));
+        assert_eq!(
+            state.timers[0].remaining,
+            chrono::TimeDelta::milliseconds(44_500)
+        );
+        assert_eq!(
+            update_timer(&state, Instant::now(), &Config::default()).block_content,
+            "0:00:44.500"
+        );
+
+        assert!(tick(&mut state, start + Duration::from_secs(61)));
+        assert_eq!(state.timers[0].remaining, chrono::TimeDelta::zero());
+        // 250ms 間隔で鳴らす
+        assert!(!tick(&mut state, start + Duration::from_millis(61_100)));
+        assert!(tick(&mut state, start + Duration::from_millis(61_250)));
+    }
+
+    #[test]
+    fn counts_overtime() {
+        let start = Instant::now();
+        let at = |ms| start + Duration::from_millis(ms);
+        let config = Config::default();
+        let mut state = TimerState::default();
+        press(&mut state, KeyCode::Char('5'), at(0));
+        press(&mut state, KeyCode::Char(' '), at(0));
+        tick(&mut state, at(87_250));
+        let display = update_timer(&state, at(87_250), &config);
+        assert_eq!(display.block_content, "+0:01:22.250");
+        assert_eq!(display.content_color, Color::Red);
+        assert!((display.analog_state.min_rad - 22.25 * PI / 30.).abs() < 1e-9);
+
+        // 停止後も超過時間を表示し続ける
+        press(&mut state, KeyCode::Char(' '), at(90_000));
+        assert_eq!(state.timers[0].status, TimerStatus::Idle);
+        tick(&mut state, at(95_000));
+        let display = update_timer(&state, at(95_000), &config);
+        assert_eq!(display.block_title, "Idle");
+        assert_eq!(display.block_content, "+0:01:22.250");
+
+        press(&mut state, KeyCode::Char('r'), at(96_000));
+        let display = update_timer(&state, at(96_000), &config);
+        assert_eq!(display.block_content, "0:00:00.000");
+        assert_eq!(display.content_color, Color::Reset);
+    }
+
+    #[test]
+    fn overtime_can_be_disabled() {
+        let start = Instant::now();
+        let config = Config {
+            timer: TimerConfig { overtime: false },
+            ..Default::default()
+        };
+        let mut state = TimerState::default();
+        press(&mut state, KeyCode::Char('5'), start);
+        press(&mut state, KeyCode::Char(' '), start);
+        tick_timer(&mut state, start + Duration::from_secs(10), &config);
+        let display = update_timer(&state, start + Duration::from_secs(10), &config);
+        assert_eq!(display.block_content, "0:00:00.000");
+    }
+
+    #[test]
+    fn dismiss_and_snooze() {
+        let start = Instant::now();
+        let at = |secs| start + Duration::from_secs(secs);
+        let config = Config::default();
+        let mut state = TimerState::default();
+        press(&mut state, KeyCode::Up, at(0));
+        press(&mut state, KeyCode::Char(' '), at(0));
+        assert!(tick(&mut state, at(61)));
+        assert_eq!(
+            update_timer(&state, at(61), &config).block_title,
+            "Time's up!"
+        );
+
+        press(&mut state, KeyCode::Char('d'), at(62));
+        assert!(!tick(&mut state, at(63)));
+        assert_eq!(update_timer(&state, at(63), &config).block_title, "Expired");
+        assert_eq!(state.timers[0].status, TimerStatus::Running);
+
+        press(&mut state, KeyCode::Char('z'), at(64));
+        assert!(!tick(&mut state, at(65)));
+        assert_eq!(state.timers[0].remaining, chrono::TimeDelta::seconds(299));
+        assert!(tick(&mut state, at(64 + 301)));
+    }
+
+    #[test]
+    fn timers_run_side_by_side() {
+        let start = Instant::now();
+        let at = |secs| start + Duration::from_secs(secs);
+        let config = Config::default();
+        let mut state = TimerState::default();
+        press(&mut state, KeyCode::Char('5'), at(0));
+        press(&mut state, KeyCode::Char('0'), at(0));
+        press(&mut state, KeyCode::Char(' '), at(0));
+        press(&mut state, KeyCode::Char('n'), at(0));
+        for c in "Tea".chars() {
+            press(&mut state, KeyCode::Char(c), at(0));
+        }
+        press(&mut state, KeyCode::Enter, at(0));
+        assert_eq!(state.selected, 1);
+        press(&mut state, KeyCode::Char('1'), at(0));
+        press(&mut state, KeyCode::Char('0'), at(0));
+        press(&mut state, KeyCode::Char(' '), at(0));
+
+        // 表示していないタイマーも進む
+        press(&mut state, KeyCode::Char('['), at(0));
+        assert!(!tick(&mut state, at(5)));
+        assert_eq!(state.timers[1].remaining, chrono::TimeDelta::seconds(5));
+        assert!(tick(&mut state, at(11)));
+        let display = update_timer(&state, at(11), &config);
+        assert_eq!(display.block_title, "Timer 1: Started");
+        assert_eq!(display.block_content, "0:00:39.000");
+
+        press(&mut state, KeyCode::Char('d'), at(12));
+        assert!(!tick(&mut state, at(13)));
+        press(&mut state, KeyCode::Char(']'), at(13));
+        press(&mut state, KeyCode::Char(']'), at(13));
+        assert_eq!(
+            update_timer(&state, at(13), &config).block_title,
+            "Tea: Expired"
+        );
+
+        press(&mut state, KeyCode::Char('x'), at(14));
+        press(&mut state, KeyCode::Char('x'), at(14));
+        assert_eq!(state.timers.len(), 1);
+        assert_eq!(update_timer(&state, at(14), &config).block_title, "Started");
+    }
+
+    #[test]
+    fn pause_keeps_remaining() {
+        let start = Instant::now();
+        let mut state = TimerState::default();
+        press(&mut state, KeyCode::Up, start);
+        press(&mut state, KeyCode::Char(' '), start);
+        press(
+            &mut state,
+            KeyCode::Char(' '),
+            start + Duration::from_secs(20),
+        );
+        assert_eq!(state.timers[0].status, TimerStatus::Paused);
+        assert_eq!(
+            update_timer(&state, Instant::now(), &Config::default()).block_title,
+            "Paused"
+        );
+
+        // 一時停止中は進まない
+        let resume = start + Duration::from_secs(100);
+        press(&mut state, KeyCode::Char(' '), resume);
+        tick(&mut state, resume + Duration::from_secs(10));
+        assert_eq!(state.timers[0].remaining, chrono::TimeDelta::seconds(30));
+    }
+
+    #[test]
+    fn reset_returns_to_idle() {
+        let start = Instant::now();
+        let mut state = TimerState::default();
+        press(&mut state, KeyCode::Up, start);
+        press(&mut state, KeyCode::Char(' '), start);
+        tick(&mut state, start + Duration::from_secs(10));
+        press(
+            &mut state,
+            KeyCode::Char('r'),
+            start + Duration::from_secs(10),
+        );
+        assert_eq!(state.timers[0].status, TimerStatus::Idle);
+        assert_eq!(state.timers[0].remaining, chrono::TimeDelta::zero());
+        assert_eq!(state.timers[0].duration, chrono::TimeDelta::minutes(1));
+    }
+
+    #[test]
+    fn edits_hours() {
+        let now = Instant::now();
+        let mut state = TimerState::default();
+        press(&mut state, KeyCode::Left, now);
+        press(&mut state, KeyCode::Left, now);
+        press(&mut state, KeyCode::Up, now);
+        press(&mut state, KeyCode::Up, now);
+        press(&mut state, KeyCode::Right, now);
+        press(&mut state, KeyCode::Up, now);
+        assert_eq!(state.timers[0].duration, chrono::TimeDelta::minutes(130));
+
+        for _ in 0..1000 {
+            press(&mut state, KeyCode::Up, now);
+        }
+        assert_eq!(state.timers[0].duration, MAX_DURATION);
+    }
+
+    #[test]
+    fn multi_hour_timer_uses_clock_hands() {
+        let state = TimerState {
+            timers: vec![Timer {
+                duration: chrono::TimeDelta::hours(2),
+                remaining: chrono::TimeDelta::seconds(3600 + 15 * 60 + 30),
+                ..Timer::new("Tea".to_string())
+            }],
+            ..Default::default()
+        };
+        let display = update_timer(&state, Instant::now(), &Config::default());
+        assert_eq!(display.block_content, "1:15:30.000");
+        let analog = display.analog_state;
+        assert!((analog.sec_rad - PI).abs() < 1e-9);
+        assert!((analog.min_rad - 15.5 * PI / 30.).abs() < 1e-9);
+        assert!((analog.hour_rad - (1. + 15.5 / 60.) * PI / 6.).abs() < 1e-9);
+    }
+
+    #[test]
+    fn digits_are_entered_like_a_microwave() {
+        let now = Instant::now();
+        let mut state = TimerState::default();
+        for c in ['1', '3', '0'] {
+            press(&mut state, KeyCode::Char(c), now);
+        }
+        assert_eq!(state.timers[0].duration, chrono::TimeDelta::seconds(90));
+        press(&mut state, KeyCode::Char('0'), now);
+        assert_eq!(state.timers[0].duration, chrono::TimeDelta::minutes(13));
+        press(&mut state, KeyCode::Backspace, now);
+        press(&mut state, KeyCode::Backspace, now);
+        assert_eq!(state.timers[0].duration, chrono::TimeDelta::seconds(13));
+
+        // 矢印で編集した後は新しく入力し直す
+        press(&mut state, KeyCode::Up, now);
+        press(&mut state, KeyCode::Char('5'), now);
+        assert_eq!(state.timers[0].duration, chrono::TimeDelta::seconds(5));
+    }
+
+    #[test]
+    fn input_line_sets_duration() {
+        let now = Instant::now();
+        let mut state = TimerState::default();
+        press(&mut state, KeyCode::Enter, now);
+        assert!(is_typing(&state));
+        for c in "1h30m".chars() {
+            press(&mut state, KeyCode::Char(c), now);
+        }
+        press(&mut state, KeyCode::Enter, now);
+        assert!(!is_typing(&state));
+        assert_eq!(state.timers[0].duration, chrono::TimeDelta::minutes(90));
+
+        press(&mut state, KeyCode::Enter, now);
+        press(&mut state, KeyCode::Char('x'), now);
+        press(&mut state, KeyCode::Enter, now);
+        assert_eq!(state.message.as_deref(), Some("invalid duration: x"));
+        assert_eq!(state.timers[0].duration, chrono::TimeDelta::minutes(90));
+    }
+
+    #[test]
+    fn parses_durations() {
+        let ok = |s| parse_duration(s).unwrap();
+        assert_eq!(ok("1h30m"), chrono::TimeDelta::minutes(90));
+        assert_eq!(ok("90s"), chrono::TimeDelta::seconds(90));
+        assert_eq!(ok("25:00"), chrono::TimeDelta::minutes(25));
+        assert_eq!(ok("1:02:03"), chrono::TimeDelta::seconds(3723));
+        assert_eq!(ok("25"), chrono::TimeDelta::minutes(25));
+        assert_eq!(ok(" 2 min 5 sec "), chrono::TimeDelta::seconds(125));
+        assert_eq!(parse_duration(""), Err(ParseDurationError::Empty));
+        assert!(parse_duration("1:60").is_err());
+        assert!(parse_duration("-5").is_err());
+        assert!(parse_duration("5x").is_err());
+        assert!(parse_duration("1:2:3:4").is_err());
+        assert_eq!(parse_duration("100h"), Err(ParseDurationError::TooLong));
+        // TimeDelta の範囲を超える値でも panic しない
+        for s in [
+            "4294967295h",
+            "4294967295:00:00",
+            "4294967295",
+            "4294967295h4294967295h",
+        ] {
+            assert_eq!(parse_duration(s), Err(ParseDurationError::TooLong), "{s}");
+        }
+    }
+
+    #[test]
+    fn presets_are_saved_and_loaded() {
+        let now = Instant::now();
+        let path =
+            std::env::temp_dir().join(format!("atclock-presets-{}.toml", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        let mut state = TimerState::default();
+        load_presets(&mut state, path.clone());
+        for (digits, name) in [("300", "Tea"), ("2500", "")] {
+            for c in digits.chars() {
+                press(&mut state, KeyCode::Char(c), now);
+            }
+            press(&mut state, KeyCode::Char('s'), now);
+            for c in name.chars() {
+                press(&mut state, KeyCode::Char(c), now);
+            }
+            press(&mut state, KeyCode::Enter, now);
+        }
+
+        let mut loaded = TimerState::default();
+        load_presets(&mut loaded, path.clone());
+        assert_eq!(loaded.presets, state.presets);
+        assert_eq!(loaded.presets[0].name, "Tea");
+        assert_eq!(loaded.presets[1].name, "25:00");
+
+        press(&mut loaded, KeyCode::Char('p'), now);
+        assert!(is_typing(&loaded));
+        press(&mut loaded, KeyCode::Char('1'), now);
+        assert!(!is_typing(&loaded));
+        assert_eq!(loaded.timers[0].duration, chrono::TimeDelta::minutes(3));
+
+        press(&mut loaded, KeyCode::Char('p'), now);
+        press(&mut loaded, KeyCode::Down, now);
+        press(&mut loaded, KeyCode::Char('d'), now);
+        let mut reloaded = TimerState::default();
+        load_presets(&mut reloaded, path.clone());
+        assert_eq!(reloaded.presets.len(), 1);
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn broken_presets_file_is_not_overwritten() {
+        let now = Instant::now();
+        let path = std::env::temp_dir().join(format!(
+            "atclock-broken-presets-{}.toml",
+            std::process::id()
+        ));
+        for text in [
+            "[[preset]]\nname = \"Tea\"\nduration = \"3m\"\n\
+             [[preset]]\nname = \"Bad\"\nduration = \"soon\"\n",
+            "[[preset]\nname = \"Tea\"\n",
+        ] {
+            std::fs::write(&path, text).unwrap();
+            let mut state = TimerState::default();
+            load_presets(&mut state, path.clone());
+            assert!(state.presets_path.is_none());
+            assert!(state.message.as_ref().unwrap().contains("not be saved"));
+            press(&mut state, KeyCode::Char('5'), now);
+            press(&mut state, KeyCode::Char('s'), now);
+            press(&mut state, KeyCode::Enter, now);
+            assert_eq!(std::fs::read_to_string(&path).unwrap(), text);
+            assert!(state.message.as_ref().unwrap().starts_with("Not saved: "));
+        }
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn hands_follow_remaining_time() {
+        let state = TimerState {
+            timers: vec![Timer {
+                remaining: chrono::TimeDelta::milliseconds(15 * 60_000 + 30_250),
+                ..Timer::new("Tea".to_string())
+            }],
+            ..Default::default()
+        };
+        let analog = update_timer(&state, Instant::now(), &Config::default()).analog_state;
+        assert!((analog.sec_rad - 250. * PI / 500.).abs() < 1e-9);
+        assert!((analog.min_rad - 30.25 * PI / 30.).abs() < 1e-9);
+        assert!((analog.hour_rad - (15. + 30.25 / 60.) * PI / 30.).abs() < 1e-9);
+    }
 }
@@ -0,0 +1,318 @@
+use chrono::{Offset, Timelike};
+use chrono_tz::Tz;
+use crossterm::event::{KeyCode, KeyEvent};
+use ratatui::{
+    Frame,
+    layout::{Constraint, Direction, Layout, Rect},
+    style::{Color, Modifier, Stylize},
+    text::{Line, Span, Text},
+};
+use serde::Deserialize;
+
+use super::clock_tab::ClockConfig;
+use super::sun::Location;
+
+/// Settings of the `[world]` table of the config.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct WorldConfig {
+    /// IANA time zone names, such as `Asia/Tokyo`.
+    pub zones: Vec<String>,
+}
+impl Default for WorldConfig {
+    fn default() -> WorldConfig {
+        WorldConfig {
+            zones: [
+                "America/Los_Angeles",
+                "America/New_York",
+                "Europe/London",
+                "Asia/Tokyo",
+            ]
+            .map(String::from)
+            .to_vec(),
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct WorldState {
+    zones: Vec<Tz>,
+    selected: usize,
+    /// Zone shown on the Clock tab instead of the local time zone.
+    clock_zone: Option<Tz>,
+    /// Zone names in the config that could not be read.
+    message: Option<String>,
+}
+
+/// Reads the zones listed in the config.
+pub fn load_zones(state: &mut WorldState, config: &WorldConfig) {
+    state.zones.clear();
+    let mut invalid = vec![];
+    for name in &config.zones {
+        match name.parse::<Tz>() {
+            Ok(tz) => state.zones.push(tz),
+            Err(_) => invalid.push(&name[..]),
+        }
+    }
+    state.message = (!invalid.is_empty()).then(|| format!("Unknown zones: {}", invalid.join(", ")));
+    state.selected = std::cmp::min(state.selected, state.zones.len().saturating_sub(1));
+}
+
+/// The city part of a zone name, e.g. `New York` for `America/New_York`.
+fn city(tz: Tz) -> String {
+    let name = tz.name();
+    name.rsplit('/').next().unwrap_or(name).replace('_', " ")
+}
+
+/// Formats a UTC offset such as `+09:00`, or `+05:45` for zones off the hour.
+fn format_offset(seconds: i32) -> String {
+    let sign = if seconds < 0 { '-' } else { '+' };
+    let minutes = seconds.abs() / 60;
+    format!("{}{:02}:{:02}", sign, minutes / 60, minutes % 60)
+}
+
+/// Whether it is daytime, taken as 6:00 to 18:00.
+fn is_day(hour: u32) -> bool {
+    (6..18).contains(&hour)
+}
+
+/// The readout of a clock at `now`, with the daylight of `location` on a 24-hour dial.
+fn update_at<Tz: chrono::TimeZone>(
+    now: chrono::DateTime<Tz>,
+    config: &ClockConfig,
+    location: Option<&Location>,
+) -> super::DisplayData
+where
+    Tz::Offset: std::fmt::Display,
+{
+    let mut display = super::clock_tab::update_chrono(now.clone(), config);
+    if let Some(location) = location {
+        display.analog_state.daylight = super::sun::daylight_arc(&now, location);
+    }
+    display
+}
+
+/// The readout of a clock in `tz`, titled with the configured `date_format`, or by default
+/// with the date and the zone abbreviation.
+fn update_zone(
+    tz: Tz,
+    local_now: chrono::DateTime<chrono::Local>,
+    config: &ClockConfig,
+    location: Option<&Location>,
+) -> super::DisplayData {
+    let now = local_now.with_timezone(&tz);
+    let mut display = update_at(now, config, location);
+    if config.date_format.is_none() {
+        display.block_title = now.format("%m-%d %a %Z").to_string();
+    }
+    display
+}
+
+/// The readout of the Clock tab, in the zone chosen here if any.
+pub fn update_clock(
+    state: &WorldState,
+    local_now: chrono::DateTime<chrono::Local>,
+    config: &ClockConfig,
+    location: Option<&Location>,
+) -> super::DisplayData {
+    match state.clock_zone {
+        Some(tz) => update_zone(tz, local_now, config, location),
+        None => update_at(local_now, config, location),
+    }
+}
+
+/// Today's date in the zone shown on the Clock tab.
+pub fn clock_today(
+    state: &WorldState,
+    local_now: chrono::DateTime<chrono::Local>,
+) -> chrono::NaiveDate {
+    match state.clock_zone {
+        Some(tz) => local_now.with_timezone(&tz).date_naive(),
+        None => local_now.date_naive(),
+    }
+}
+
+/// The readout of the selected zone.
+pub fn update_world(
+    state: &WorldState,
+    local_now: chrono::DateTime<chrono::Local>,
+    config: &ClockConfig,
+    location: Option<&Location>,
+) -> super::DisplayData {
+    match state.zones.get(state.selected) {
+        Some(&tz) => update_zone(tz, local_now, config, location),
+        None => update_at(local_now, config, location),
+    }
+}
+
+pub fn handle_key_event(state: &mut WorldState, key: &KeyEvent) {
+    match (key.modifiers, key.code) {
+        (_, KeyCode::Up) => state.selected = state.selected.saturating_sub(1),
+        (_, KeyCode::Down) => {
+            state.selected = std::cmp::min(state.selected + 1, state.zones.len().saturating_sub(1));
+        }
+        (_, KeyCode::Enter) => state.clock_zone = state.zones.get(state.selected).copied(),
+        (_, KeyCode::Char('l') | KeyCode::Backspace) => state.clock_zone = None,
+        _ => {}
+    }
+}
+
+pub fn render_panel(
+    frame: &mut Frame,
+    panel_area: Rect,
+    state: &WorldState,
+    local_now: chrono::DateTime<chrono::Local>,
+) {
+    let panel_layout = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints(vec![
+            Constraint::Min(0),
+            Constraint::Length(1),
+            Constraint::Length(1),
+            Constraint::Length(1),
+            Constraint::Length(std::cmp::max(state.zones.len(), 1) as u16),
+            Constraint::Length(1),
+            Constraint::Length(1),
+            Constraint::Min(0),
+        ])
+        .split(panel_area);
+
+    frame.render_widget(
+        Text::from("↑↓: Select  [Enter]: Show on Clock")
+            .add_modifier(Modifier::DIM)
+            .centered(),
+        panel_layout[1],
+    );
+    frame.render_widget(
+        Text::from("[l]: Back to local time")
+            .add_modifier(Modifier::DIM)
+            .centered(),
+        panel_layout[2],
+    );
+
+    if state.zones.is_empty() {
+        frame.render_widget(
+            Text::from("Add zones to [world] in config.toml")
+                .add_modifier(Modifier::ITALIC)
+                .centered(),
+            panel_layout[4],
+        );
+    }
+    let lines: Vec<Line> = state
+        .zones
+        .iter()
+        .enumerate()
+        .map(|(i, &tz)| {
+            let now = local_now.with_timezone(&tz);
+            let (mark, color) = if is_day(now.hour()) {
+                ("☀", Color::Yellow)
+            } else {
+                ("☾", Color::Blue)
+            };
+            let line = Line::from(vec![
+                Span::from(format!(
+                    "{}{} ",
+                    if i == state.selected { ">" } else { " " },
+                    if state.clock_zone == Some(tz) {
+                        "*"
+                    } else {
+                        " "
+                    },
+                )),
+                Span::from(mark).fg(color),
+                Span::from(format!(
+                    " {:<11.11} {} {} {}",
+                    city(tz),
+                    now.format("%H:%M"),
+                    now.format("%a %d"),
+                    format_offset(now.offset().fix().local_minus_utc())
+                )),
+            ]);
+            if i == state.selected {
+                line.add_modifier(Modifier::BOLD)
+            } else {
+                line.add_modifier(Modifier::DIM)
+            }
+        })
+        .collect();
+    frame.render_widget(Text::from(lines).centered(), panel_layout[4]);
+
+    if let Some(message) = &state.message {
+        frame.render_widget(
+            Text::from(&message[..])
+                .add_modifier(Modifier::ITALIC)
+                .centered(),
+            panel_layout[6],
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::time_source::{FakeTimeSource, TimeSource};
+    use crossterm::event::KeyModifiers;
+    use std::f64::consts::PI;
+
+    fn press(state: &mut WorldState, code: KeyCode) {
+        handle_key_event(state, &KeyEvent::new(code, KeyModifiers::NONE));
+    }
+
+    #[test]
+    fn shows_other_zones() {
+        let config = WorldConfig {
+            zones: ["Asia/Kathmandu", "America/New_York", "Mars/Olympus"]
+                .map(String::from)
+                .to_vec(),
+        };
+        let mut state = WorldState::default();
+        load_zones(&mut state, &config);
+        assert_eq!(state.zones.len(), 2);
+        assert_eq!(
+            state.message.as_deref(),
+            Some("Unknown zones: Mars/Olympus")
+        );
+
+        let local = FakeTimeSource::at_utc(2025, 7, 1, 3, 0, 0).local();
+        let display = update_world(&state, local, &ClockConfig::default(), None);
+        assert_eq!(display.block_content, "08:45:00 AM");
+        assert_eq!(display.block_title, "07-01 Tue +0545");
+        assert!((display.analog_state.min_rad - 45. * PI / 30.).abs() < 1e-9);
+
+        press(&mut state, KeyCode::Down);
+        press(&mut state, KeyCode::Enter);
+        let display = update_clock(&state, local, &ClockConfig::default(), None);
+        assert_eq!(display.block_content, "11:00:00 PM");
+        assert_eq!(display.block_title, "06-30 Mon EDT");
+        assert_eq!(
+            clock_today(&state, local),
+            chrono::NaiveDate::from_ymd_opt(2025, 6, 30).unwrap()
+        );
+
+        let config = ClockConfig {
+            date_format: Some("iso8601".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(
+            update_clock(&state, local, &config, None).block_title,
+            "2025-06-30T23:00:00-04:00"
+        );
+
+        press(&mut state, KeyCode::Char('l'));
+        assert_eq!(
+            update_clock(&state, local, &ClockConfig::default(), None).block_title,
+            local.format("%Y-%m-%d %a").to_string()
+        );
+    }
+
+    #[test]
+    fn formats_offsets() {
+        assert_eq!(format_offset(9 * 3600), "+09:00");
+        assert_eq!(format_offset(-(3 * 3600 + 30 * 60)), "-03:30");
+        assert_eq!(format_offset(0), "+00:00");
+        assert_eq!(
+            city("America/Argentina/Buenos_Aires".parse().unwrap()),
+            "Buenos Aires"
+        );
+    }
+}